@@ -0,0 +1,174 @@
+//! Pricing math shared with off-chain clients
+//!
+//! Fees, Dutch prices and minimum bids use nothing beyond `core`, so
+//! frontends compiled to wasm price auctions from the same code the program
+//! runs. The `bytemuck` feature adds the Pod derives the program's zero-copy
+//! accounts need; without it the crate has no dependencies.
+
+#![no_std]
+
+#[cfg(feature = "bytemuck")]
+use bytemuck::{CheckedBitPattern, NoUninit, Zeroable};
+
+/// Fee rate in basis points (0.5% = 50)
+pub const FEE_RATE: u64 = 50;
+
+/// Fee denominator (basis points)
+pub const FEE_DENOMINATOR: u64 = 10000;
+
+/// Traditional auction parameters
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "bytemuck", derive(CheckedBitPattern, NoUninit, Zeroable))]
+#[repr(C)]
+pub struct TraditionalParams {
+    /// Minimum first bid
+    pub start_amount: u64,
+    /// Minimum bid increase (see `min_raise`)
+    pub increment: u64,
+    /// Minimum price to auto-finalize
+    pub reserve_price: u64,
+    /// Auction end timestamp
+    pub deadline: i64,
+    /// Dealer acceptance deadline (24h after auction end)
+    pub acceptance_deadline: i64,
+    /// Whether reserve price was met
+    pub reserve_met: bool,
+    /// Whether raises also follow an `IncrementTable` PDA
+    pub has_increment_table: bool,
+    /// Minimum bid increase in basis points of the current bid, used when
+    /// larger than `increment`
+    pub increment_bps: u16,
+    /// Unit bids must be multiples of, or 0 for any amount
+    pub tick_size: u32,
+}
+
+impl TraditionalParams {
+    /// Smallest raise over `current_bid`: `increment` or `increment_bps` of
+    /// the current bid rounded up, whichever is larger
+    pub fn min_raise(&self, current_bid: u64) -> u64 {
+        let pct = (current_bid as u128 * self.increment_bps as u128)
+            .div_ceil(FEE_DENOMINATOR as u128) as u64;
+        self.increment.max(pct)
+    }
+
+    /// Whether `amount` is a whole number of ticks
+    pub fn on_tick(&self, amount: u64) -> bool {
+        self.tick_size == 0 || amount.is_multiple_of(self.tick_size as u64)
+    }
+
+    /// Smallest bid accepted over `current_bid` at the listed amounts, or
+    /// `None` on overflow. Auctions priced in USD or with an increment table
+    /// adjust the inputs of [`min_bid`] instead.
+    pub fn min_bid(&self, current_bid: u64) -> Option<u64> {
+        min_bid(current_bid, self.start_amount, self.min_raise(current_bid))
+    }
+}
+
+/// Dutch auction parameters
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "bytemuck", derive(CheckedBitPattern, NoUninit, Zeroable))]
+#[repr(C)]
+pub struct DutchParams {
+    /// Initial price
+    pub start_price: u64,
+    /// Price decrease per interval
+    pub decrease_amount: u64,
+    /// Seconds between decreases
+    pub interval: i64,
+    /// Floor price
+    pub minimum_price: u64,
+    /// Auction end timestamp
+    pub deadline: i64,
+    /// When price starts decreasing
+    pub start_time: i64,
+}
+
+/// Penny auction parameters
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "bytemuck", derive(CheckedBitPattern, NoUninit, Zeroable))]
+#[repr(C)]
+pub struct PennyParams {
+    /// Fixed bid increment (paid to dealer)
+    pub increment: u64,
+    /// Timer reset duration (typically 5 minutes)
+    pub timer_duration: i64,
+    /// Current deadline (resets on each bid)
+    pub current_deadline: i64,
+    /// Running total of payments to dealer
+    pub total_paid: u64,
+    /// Timestamp of last bid
+    pub last_bid_time: i64,
+}
+
+/// Calculate fee and net amount
+pub fn calculate_fee(amount: u64) -> (u64, u64) {
+    calculate_fee_at(amount, FEE_RATE)
+}
+
+/// Calculate fee and net amount at `fee_rate` basis points
+pub fn calculate_fee_at(amount: u64, fee_rate: u64) -> (u64, u64) {
+    let fee = amount.saturating_mul(fee_rate) / FEE_DENOMINATOR;
+    let net = amount.saturating_sub(fee);
+    (fee, net)
+}
+
+/// Calculate Dutch auction current price
+pub fn calculate_dutch_price(params: &DutchParams, current_time: i64) -> u64 {
+    if current_time <= params.start_time {
+        return params.start_price;
+    }
+
+    let elapsed = current_time.saturating_sub(params.start_time);
+    let intervals = elapsed / params.interval;
+    let total_decrease = (intervals as u64).saturating_mul(params.decrease_amount);
+
+    let current_price = params.start_price.saturating_sub(total_decrease);
+    current_price.max(params.minimum_price)
+}
+
+/// Breakpoints of a Dutch auction's price as `(timestamp, price)`, from
+/// `start_time` until the price reaches its floor or the deadline passes.
+/// Each price holds until the next breakpoint, matching
+/// [`calculate_dutch_price`].
+pub fn dutch_price_schedule(params: &DutchParams) -> impl Iterator<Item = (i64, u64)> {
+    let params = *params;
+    let first =
+        (params.start_time <= params.deadline).then_some((params.start_time, params.start_price));
+    core::iter::successors(first, move |&(time, price)| {
+        if params.interval <= 0 || params.decrease_amount == 0 || price <= params.minimum_price {
+            return None;
+        }
+        let time = time.checked_add(params.interval)?;
+        let price = price
+            .saturating_sub(params.decrease_amount)
+            .max(params.minimum_price);
+        (time <= params.deadline).then_some((time, price))
+    })
+}
+
+/// Time after `now` at which a Dutch auction's price next drops, or `None`
+/// once it has reached its floor or no drop is left before the deadline
+pub fn next_dutch_price_change(params: &DutchParams, now: i64) -> Option<i64> {
+    if params.interval <= 0 || params.decrease_amount == 0 {
+        return None;
+    }
+    if calculate_dutch_price(params, now) <= params.minimum_price {
+        return None;
+    }
+    let intervals = now.saturating_sub(params.start_time).max(0) / params.interval + 1;
+    let time = params
+        .start_time
+        .checked_add(intervals.checked_mul(params.interval)?)?;
+    (time <= params.deadline).then_some(time)
+}
+
+/// Smallest bid accepted over `current_bid` (0 before the first bid):
+/// `start_amount` opens, later bids raise by at least `min_raise`. `None` on
+/// overflow.
+pub fn min_bid(current_bid: u64, start_amount: u64, min_raise: u64) -> Option<u64> {
+    if current_bid == 0 {
+        Some(start_amount)
+    } else {
+        current_bid.checked_add(min_raise)
+    }
+}
@@ -0,0 +1,347 @@
+//! Compute-unit benchmarks
+//!
+//! Runs a traditional auction end to end under `solana-program-test` and
+//! prints the compute units each instruction consumed.
+//!
+//! ```text
+//! cargo build-sbf
+//! SBF_OUT_DIR=target/deploy cargo bench --bench compute_units
+//! ```
+//!
+//! Budgets are only enforced against the SBF build: without `SBF_OUT_DIR` the
+//! program runs as a native builtin and its CU numbers are meaningless.
+
+use auctions::{
+    instruction::{self, CreateIndexes, GateAccounts},
+    pda::find_auction_address,
+    state::{Auction, PodAccount},
+};
+use solana_program_test::{processor, BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+/// Regression ceilings per benchmarked step; tighten as paths are optimized
+const BUDGETS: &[(&str, u64)] = &[
+    ("Initialize", 15_000),
+    ("InitFeeVault", 40_000),
+    ("CreateTraditionalAuction", 80_000),
+    ("DepositTokens", 45_000),
+    ("BidTraditional (first bid)", 45_000),
+    ("BidTraditional (outbid)", 60_000),
+    ("WithdrawLosingBid", 30_000),
+    ("FinalizeAuction", 70_000),
+];
+
+const AUCTION_ID: [u8; 32] = [7; 32];
+
+struct Bench {
+    context: ProgramTestContext,
+    results: Vec<(&'static str, u64)>,
+}
+
+impl Bench {
+    async fn new() -> Self {
+        let program_test = ProgramTest::new(
+            "auctions",
+            auctions::id(),
+            processor!(auctions::process_instruction),
+        );
+        Self {
+            context: program_test.start_with_context().await,
+            results: Vec::new(),
+        }
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    fn banks(&mut self) -> &mut BanksClient {
+        &mut self.context.banks_client
+    }
+
+    /// Send `instructions` without recording their cost
+    async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+        let blockhash = self.banks().get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer()),
+            &all_signers,
+            blockhash,
+        );
+        self.banks().process_transaction(transaction).await.unwrap();
+    }
+
+    /// Send a single instruction and record the compute units it consumed
+    async fn measure(&mut self, name: &'static str, ix: Instruction, signers: &[&Keypair]) {
+        let blockhash = self.banks().get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction =
+            Transaction::new_signed_with_payer(&[ix], Some(&self.payer()), &all_signers, blockhash);
+
+        let outcome = self
+            .banks()
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        if let Err(err) = outcome.result {
+            let logs = outcome.metadata.map(|m| m.log_messages).unwrap_or_default();
+            panic!("{name} failed: {err}\n{}", logs.join("\n"));
+        }
+        let units = outcome.metadata.map_or(0, |m| m.compute_units_consumed);
+        self.results.push((name, units));
+    }
+
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.banks().get_rent().await.unwrap();
+        let payer = self.payer();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    0,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await;
+        mint.pubkey()
+    }
+
+    async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.banks().get_rent().await.unwrap();
+        let payer = self.payer();
+        let mut instructions = vec![
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        if amount > 0 {
+            instructions.push(
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    mint,
+                    &account.pubkey(),
+                    &payer,
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            );
+        }
+        self.send(&instructions, &[&account]).await;
+        account.pubkey()
+    }
+
+    async fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        let payer = self.payer();
+        self.send(
+            &[system_instruction::transfer(
+                &payer,
+                &keypair.pubkey(),
+                1_000_000_000,
+            )],
+            &[],
+        )
+        .await;
+        keypair
+    }
+
+    async fn auction(&mut self) -> Auction {
+        let (address, _) = find_auction_address(&auctions::id(), &AUCTION_ID);
+        let account = self.banks().get_account(address).await.unwrap().unwrap();
+        Auction::load(&account.data).unwrap()
+    }
+
+    /// Print the results and return the steps over budget
+    fn report(&self, enforce: bool) -> Vec<&'static str> {
+        let mut over = Vec::new();
+        println!("{:<36} {:>10} {:>10}", "instruction", "CU", "budget");
+        for (name, units) in &self.results {
+            let budget = BUDGETS
+                .iter()
+                .find(|(step, _)| step == name)
+                .map_or(u64::MAX, |(_, budget)| *budget);
+            let flag = if *units > budget { "  OVER" } else { "" };
+            println!("{name:<36} {units:>10} {budget:>10}{flag}");
+            if enforce && *units > budget {
+                over.push(*name);
+            }
+        }
+        over
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let program_id = auctions::id();
+    let enforce = std::env::var("BPF_OUT_DIR").is_ok() || std::env::var("SBF_OUT_DIR").is_ok();
+    let mut bench = Bench::new().await;
+    let dealer = bench.payer();
+
+    bench
+        .measure("Initialize", instruction::initialize(&program_id, &dealer), &[])
+        .await;
+
+    let mint = bench.create_mint().await;
+    let dealer_token = bench.create_token_account(&mint, &dealer, 10).await;
+    bench
+        .measure(
+            "InitFeeVault",
+            instruction::init_fee_vault(&program_id, &dealer, &mint),
+            &[],
+        )
+        .await;
+    let first = bench.funded_keypair().await;
+    let first_token = bench.create_token_account(&mint, &first.pubkey(), 5_000).await;
+    let second = bench.funded_keypair().await;
+    let second_token = bench
+        .create_token_account(&mint, &second.pubkey(), 5_000)
+        .await;
+
+    let clock: Clock = bench.banks().get_sysvar().await.unwrap();
+    let deadline = clock.unix_timestamp + 3_600;
+    bench
+        .measure(
+            "CreateTraditionalAuction",
+            instruction::create_traditional_auction(
+                &program_id,
+                &dealer,
+                &mint,
+                &CreateIndexes::default(),
+                AUCTION_ID,
+                1_000,
+                100,
+                0,
+                deadline,
+                0,
+                "",
+                &[],
+                [0; 32],
+            ),
+            &[],
+        )
+        .await;
+
+    let auction = bench.auction().await;
+    bench
+        .measure(
+            "DepositTokens",
+            instruction::deposit_tokens(
+                &program_id,
+                &dealer,
+                &auction,
+                &dealer_token,
+                &mint,
+                10,
+                0,
+            ),
+            &[],
+        )
+        .await;
+
+    let auction = bench.auction().await;
+    bench
+        .measure(
+            "BidTraditional (first bid)",
+            instruction::bid_traditional(
+                &program_id,
+                &auction,
+                &first.pubkey(),
+                &first_token,
+                &first_token,
+                1_000,
+                None,
+                &GateAccounts::default(),
+            ),
+            &[&first],
+        )
+        .await;
+
+    let auction = bench.auction().await;
+    bench
+        .measure(
+            "BidTraditional (outbid)",
+            instruction::bid_traditional(
+                &program_id,
+                &auction,
+                &second.pubkey(),
+                &second_token,
+                &first_token,
+                1_100,
+                None,
+                &GateAccounts::default(),
+            ),
+            &[&second],
+        )
+        .await;
+
+    let auction = bench.auction().await;
+    bench
+        .measure(
+            "WithdrawLosingBid",
+            instruction::withdraw_losing_bid(&program_id, &auction, &first.pubkey(), &first_token),
+            &[&first],
+        )
+        .await;
+
+    bench.context.set_sysvar(&Clock {
+        unix_timestamp: deadline + 1,
+        ..clock
+    });
+    let auction = bench.auction().await;
+    bench
+        .measure(
+            "FinalizeAuction",
+            instruction::finalize_auction(
+                &program_id,
+                &auction,
+                &dealer,
+                &dealer_token,
+                &second_token,
+            ),
+            &[],
+        )
+        .await;
+
+    let over = bench.report(enforce);
+    if !enforce {
+        println!("\nnative run: budgets not enforced (set SBF_OUT_DIR to measure the SBF build)");
+    }
+    assert!(over.is_empty(), "over compute budget: {over:?}");
+}
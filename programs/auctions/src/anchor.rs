@@ -0,0 +1,476 @@
+//! Anchor interoperability
+//!
+//! Enabled by the `anchor` feature. Program accounts implement Anchor's
+//! account traits, so an Anchor program can take them as
+//! `Account<'info, Auction>`, and the [`cpi`] module mirrors the module
+//! `declare_program!` would generate for the bidder-facing instructions.
+//!
+//! The on-chain discriminators are already Anchor's `account:<Name>` hashes,
+//! so no data conversion is involved.
+
+use std::io::Write;
+
+use anchor_lang::{
+    error::ErrorCode, AccountDeserialize, AccountSerialize, Discriminator, Id, Owner, Result,
+};
+use solana_program::pubkey::Pubkey;
+
+use crate::state::{
+    AllowlistPass, Auction, AuctionItem, AuctionSeries, BidDelegate, BidDeposit, BidHistory,
+    BidReceipt, BidSession, BidderStats, BlacklistEntry, BondConfig, BondingCurve, ClockIntent,
+    ClockSale, CoListing, CollectionBid, DealerBond, DealerIndex, DealerIndexPage, DealerProfile,
+    DealerStats, Dispute, DutchOracle, FeeBurnEntry, FeeExemption, FeeOverride, FeeVault,
+    HarbergerListing, IncrementTable, MintStats, PayoutSplits, PodAccount, ProceedsVesting,
+    ProgramAccount, ProgramState, RegistryPage, SettlementSwap, UnitSale, UsdPricing,
+    DISCRIMINATOR_LEN,
+};
+
+/// Marker type for `Program<'info, Auctions>`
+#[derive(Debug, Clone, Copy)]
+pub struct Auctions;
+
+impl Id for Auctions {
+    fn id() -> Pubkey {
+        crate::id()
+    }
+}
+
+/// Check the leading discriminator, reporting failures as Anchor does
+fn check_discriminator(buf: &[u8], discriminator: &[u8; DISCRIMINATOR_LEN]) -> Result<()> {
+    if buf.len() < DISCRIMINATOR_LEN {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if buf[..DISCRIMINATOR_LEN] != *discriminator {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    Ok(())
+}
+
+macro_rules! impl_borsh_account {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl Discriminator for $ty {
+            const DISCRIMINATOR: [u8; 8] = <$ty as ProgramAccount>::DISCRIMINATOR;
+        }
+
+        impl Owner for $ty {
+            fn owner() -> Pubkey {
+                crate::id()
+            }
+        }
+
+        impl AccountDeserialize for $ty {
+            fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+                check_discriminator(buf, &<$ty as ProgramAccount>::DISCRIMINATOR)?;
+                Self::try_deserialize_unchecked(buf)
+            }
+
+            fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+                let mut data = buf
+                    .get(DISCRIMINATOR_LEN..)
+                    .ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+                borsh::BorshDeserialize::deserialize(&mut data)
+                    .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+            }
+        }
+
+        impl AccountSerialize for $ty {
+            fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+                writer
+                    .write_all(&<$ty as ProgramAccount>::DISCRIMINATOR)
+                    .and_then(|_| borsh::to_writer(writer, self))
+                    .map_err(|_| ErrorCode::AccountDidNotSerialize.into())
+            }
+        }
+    )+};
+}
+
+macro_rules! impl_pod_account {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl Discriminator for $ty {
+            const DISCRIMINATOR: [u8; 8] = <$ty as PodAccount>::DISCRIMINATOR;
+        }
+
+        impl Owner for $ty {
+            fn owner() -> Pubkey {
+                crate::id()
+            }
+        }
+
+        impl AccountDeserialize for $ty {
+            fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+                check_discriminator(buf, &<$ty as PodAccount>::DISCRIMINATOR)?;
+                Self::try_deserialize_unchecked(buf)
+            }
+
+            fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+                let bytes = buf
+                    .get(DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + std::mem::size_of::<Self>())
+                    .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+                bytemuck::checked::try_pod_read_unaligned(bytes)
+                    .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+            }
+        }
+
+        impl AccountSerialize for $ty {
+            fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+                writer
+                    .write_all(&<$ty as PodAccount>::DISCRIMINATOR)
+                    .and_then(|_| writer.write_all(bytemuck::bytes_of(self)))
+                    .map_err(|_| ErrorCode::AccountDidNotSerialize.into())
+            }
+        }
+    )+};
+}
+
+impl_borsh_account!(
+    ProgramState,
+    FeeVault,
+    BlacklistEntry,
+    DealerProfile,
+    BondConfig,
+    DealerBond,
+    AllowlistPass,
+    BidHistory,
+    BidReceipt,
+    DealerIndex,
+    DealerIndexPage,
+    RegistryPage,
+    MintStats,
+    DealerStats,
+    BidderStats,
+    PayoutSplits,
+    CoListing,
+    BidSession,
+    UsdPricing,
+    DutchOracle,
+    IncrementTable,
+    BidDeposit,
+    Dispute,
+    ProceedsVesting,
+    FeeBurnEntry,
+    FeeExemption,
+    FeeOverride,
+    SettlementSwap,
+    AuctionSeries,
+    ClockSale,
+    ClockIntent,
+    BondingCurve,
+    HarbergerListing,
+    CollectionBid,
+    BidDelegate,
+    UnitSale,
+);
+
+impl_pod_account!(Auction, AuctionItem);
+
+/// Typed CPI into the auction program
+///
+/// Account structs list the fixed accounts of each instruction in processor
+/// order. Per-auction trailing accounts (allowlist pass, gate holdings, bid
+/// history, bid deposits and, with `noop-events`, the Noop program) go in
+/// `CpiContext::remaining_accounts`, as does the SPL Memo program when a bid
+/// carries a memo.
+pub mod cpi {
+    use anchor_lang::{context::CpiContext, Result, ToAccountInfos, ToAccountMetas};
+    use solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    };
+
+    use crate::instruction::AuctionInstruction;
+
+    /// Invoke `instruction` with the context's accounts followed by its
+    /// remaining accounts
+    fn invoke<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+        ctx: CpiContext<'_, '_, '_, 'info, T>,
+        instruction: &AuctionInstruction,
+    ) -> Result<()> {
+        let mut metas = ctx.accounts.to_account_metas(None);
+        metas.extend(ctx.remaining_accounts.iter().map(|account| {
+            if account.is_writable {
+                AccountMeta::new(*account.key, account.is_signer)
+            } else {
+                AccountMeta::new_readonly(*account.key, account.is_signer)
+            }
+        }));
+        let mut infos = ctx.accounts.to_account_infos();
+        infos.extend(ctx.remaining_accounts);
+        infos.push(ctx.program.clone());
+
+        let ix = Instruction {
+            program_id: *ctx.program.key,
+            accounts: metas,
+            data: borsh::to_vec(instruction)?,
+        };
+        invoke_signed(&ix, &infos, ctx.signer_seeds).map_err(Into::into)
+    }
+
+    /// `BidTraditional`
+    pub fn bid_traditional<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, accounts::BidTraditional<'info>>,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        invoke(ctx, &AuctionInstruction::BidTraditional { amount, memo })
+    }
+
+    /// `BuyDutch`
+    pub fn buy_dutch<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, accounts::PayDealer<'info>>,
+        max_price: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        invoke(ctx, &AuctionInstruction::BuyDutch { max_price, memo })
+    }
+
+    /// `BidPenny`
+    pub fn bid_penny<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, accounts::PayDealer<'info>>,
+        memo: Option<String>,
+    ) -> Result<()> {
+        invoke(ctx, &AuctionInstruction::BidPenny { memo })
+    }
+
+    /// `FinalizeAuction`
+    pub fn finalize_auction<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, accounts::FinalizeAuction<'info>>,
+    ) -> Result<()> {
+        invoke(ctx, &AuctionInstruction::FinalizeAuction)
+    }
+
+    /// `WithdrawLosingBid`
+    pub fn withdraw_losing_bid<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, accounts::WithdrawLosingBid<'info>>,
+    ) -> Result<()> {
+        invoke(ctx, &AuctionInstruction::WithdrawLosingBid)
+    }
+
+    /// Account structs for [`cpi`](super) calls
+    pub mod accounts {
+        use anchor_lang::{ToAccountInfos, ToAccountMetas};
+        use solana_program::{account_info::AccountInfo, instruction::AccountMeta};
+
+        /// Accounts for `BidTraditional`
+        #[derive(Clone)]
+        pub struct BidTraditional<'info> {
+            pub bidder: AccountInfo<'info>,
+            pub auction: AccountInfo<'info>,
+            pub escrow: AccountInfo<'info>,
+            pub bidder_token: AccountInfo<'info>,
+            pub previous_bidder_token: AccountInfo<'info>,
+            pub program_state: AccountInfo<'info>,
+            pub token_program: AccountInfo<'info>,
+            pub bid_receipt: AccountInfo<'info>,
+            pub system_program: AccountInfo<'info>,
+            pub previous_bidder: AccountInfo<'info>,
+            pub payment_mint: AccountInfo<'info>,
+            pub associated_token_program: AccountInfo<'info>,
+        }
+
+        impl ToAccountMetas for BidTraditional<'_> {
+            fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+                vec![
+                    AccountMeta::new(*self.bidder.key, is_signer.unwrap_or(true)),
+                    AccountMeta::new(*self.auction.key, false),
+                    AccountMeta::new(*self.escrow.key, false),
+                    AccountMeta::new(*self.bidder_token.key, false),
+                    AccountMeta::new(*self.previous_bidder_token.key, false),
+                    AccountMeta::new_readonly(*self.program_state.key, false),
+                    AccountMeta::new_readonly(*self.token_program.key, false),
+                    AccountMeta::new(*self.bid_receipt.key, false),
+                    AccountMeta::new_readonly(*self.system_program.key, false),
+                    AccountMeta::new_readonly(*self.previous_bidder.key, false),
+                    AccountMeta::new_readonly(*self.payment_mint.key, false),
+                    AccountMeta::new_readonly(*self.associated_token_program.key, false),
+                ]
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for BidTraditional<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                vec![
+                    self.bidder.clone(),
+                    self.auction.clone(),
+                    self.escrow.clone(),
+                    self.bidder_token.clone(),
+                    self.previous_bidder_token.clone(),
+                    self.program_state.clone(),
+                    self.token_program.clone(),
+                    self.bid_receipt.clone(),
+                    self.system_program.clone(),
+                    self.previous_bidder.clone(),
+                    self.payment_mint.clone(),
+                    self.associated_token_program.clone(),
+                ]
+            }
+        }
+
+        /// Accounts for `BuyDutch` and `BidPenny`, which pay the dealer directly
+        #[derive(Clone)]
+        pub struct PayDealer<'info> {
+            pub bidder: AccountInfo<'info>,
+            pub auction: AccountInfo<'info>,
+            pub bidder_token: AccountInfo<'info>,
+            pub dealer_token: AccountInfo<'info>,
+            pub fee_vault_token: AccountInfo<'info>,
+            pub fee_vault: AccountInfo<'info>,
+            pub program_state: AccountInfo<'info>,
+            pub token_program: AccountInfo<'info>,
+            pub system_program: AccountInfo<'info>,
+            pub bid_receipt: AccountInfo<'info>,
+            pub dealer: AccountInfo<'info>,
+            pub payment_mint: AccountInfo<'info>,
+            pub associated_token_program: AccountInfo<'info>,
+            pub mint_stats: AccountInfo<'info>,
+            pub dealer_stats: AccountInfo<'info>,
+        }
+
+        impl ToAccountMetas for PayDealer<'_> {
+            fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+                vec![
+                    AccountMeta::new(*self.bidder.key, is_signer.unwrap_or(true)),
+                    AccountMeta::new(*self.auction.key, false),
+                    AccountMeta::new(*self.bidder_token.key, false),
+                    AccountMeta::new(*self.dealer_token.key, false),
+                    AccountMeta::new(*self.fee_vault_token.key, false),
+                    AccountMeta::new(*self.fee_vault.key, false),
+                    AccountMeta::new_readonly(*self.program_state.key, false),
+                    AccountMeta::new_readonly(*self.token_program.key, false),
+                    AccountMeta::new_readonly(*self.system_program.key, false),
+                    AccountMeta::new(*self.bid_receipt.key, false),
+                    AccountMeta::new_readonly(*self.dealer.key, false),
+                    AccountMeta::new_readonly(*self.payment_mint.key, false),
+                    AccountMeta::new_readonly(*self.associated_token_program.key, false),
+                    AccountMeta::new(*self.mint_stats.key, false),
+                    AccountMeta::new(*self.dealer_stats.key, false),
+                ]
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for PayDealer<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                vec![
+                    self.bidder.clone(),
+                    self.auction.clone(),
+                    self.bidder_token.clone(),
+                    self.dealer_token.clone(),
+                    self.fee_vault_token.clone(),
+                    self.fee_vault.clone(),
+                    self.program_state.clone(),
+                    self.token_program.clone(),
+                    self.system_program.clone(),
+                    self.bid_receipt.clone(),
+                    self.dealer.clone(),
+                    self.payment_mint.clone(),
+                    self.associated_token_program.clone(),
+                    self.mint_stats.clone(),
+                    self.dealer_stats.clone(),
+                ]
+            }
+        }
+
+        /// Accounts for `FinalizeAuction`
+        #[derive(Clone)]
+        pub struct FinalizeAuction<'info> {
+            pub caller: AccountInfo<'info>,
+            pub auction: AccountInfo<'info>,
+            pub escrow: AccountInfo<'info>,
+            pub dealer_token: AccountInfo<'info>,
+            pub winner_token: AccountInfo<'info>,
+            pub fee_vault_token: AccountInfo<'info>,
+            pub fee_vault: AccountInfo<'info>,
+            pub program_state: AccountInfo<'info>,
+            pub token_program: AccountInfo<'info>,
+            pub system_program: AccountInfo<'info>,
+            pub dealer: AccountInfo<'info>,
+            pub winner: AccountInfo<'info>,
+            pub payment_mint: AccountInfo<'info>,
+            pub associated_token_program: AccountInfo<'info>,
+            pub mint_stats: AccountInfo<'info>,
+            pub dealer_stats: AccountInfo<'info>,
+        }
+
+        impl ToAccountMetas for FinalizeAuction<'_> {
+            fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+                vec![
+                    AccountMeta::new(*self.caller.key, is_signer.unwrap_or(true)),
+                    AccountMeta::new(*self.auction.key, false),
+                    AccountMeta::new(*self.escrow.key, false),
+                    AccountMeta::new(*self.dealer_token.key, false),
+                    AccountMeta::new(*self.winner_token.key, false),
+                    AccountMeta::new(*self.fee_vault_token.key, false),
+                    AccountMeta::new(*self.fee_vault.key, false),
+                    AccountMeta::new_readonly(*self.program_state.key, false),
+                    AccountMeta::new_readonly(*self.token_program.key, false),
+                    AccountMeta::new_readonly(*self.system_program.key, false),
+                    AccountMeta::new_readonly(*self.dealer.key, false),
+                    AccountMeta::new_readonly(*self.winner.key, false),
+                    AccountMeta::new_readonly(*self.payment_mint.key, false),
+                    AccountMeta::new_readonly(*self.associated_token_program.key, false),
+                    AccountMeta::new(*self.mint_stats.key, false),
+                    AccountMeta::new(*self.dealer_stats.key, false),
+                ]
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for FinalizeAuction<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                vec![
+                    self.caller.clone(),
+                    self.auction.clone(),
+                    self.escrow.clone(),
+                    self.dealer_token.clone(),
+                    self.winner_token.clone(),
+                    self.fee_vault_token.clone(),
+                    self.fee_vault.clone(),
+                    self.program_state.clone(),
+                    self.token_program.clone(),
+                    self.system_program.clone(),
+                    self.dealer.clone(),
+                    self.winner.clone(),
+                    self.payment_mint.clone(),
+                    self.associated_token_program.clone(),
+                    self.mint_stats.clone(),
+                    self.dealer_stats.clone(),
+                ]
+            }
+        }
+
+        /// Accounts for `WithdrawLosingBid`
+        #[derive(Clone)]
+        pub struct WithdrawLosingBid<'info> {
+            pub bidder: AccountInfo<'info>,
+            pub auction: AccountInfo<'info>,
+            pub escrow: AccountInfo<'info>,
+            pub bidder_token: AccountInfo<'info>,
+            pub bid_deposit: AccountInfo<'info>,
+            pub token_program: AccountInfo<'info>,
+        }
+
+        impl ToAccountMetas for WithdrawLosingBid<'_> {
+            fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+                vec![
+                    AccountMeta::new(*self.bidder.key, is_signer.unwrap_or(true)),
+                    AccountMeta::new_readonly(*self.auction.key, false),
+                    AccountMeta::new(*self.escrow.key, false),
+                    AccountMeta::new(*self.bidder_token.key, false),
+                    AccountMeta::new(*self.bid_deposit.key, false),
+                    AccountMeta::new_readonly(*self.token_program.key, false),
+                ]
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for WithdrawLosingBid<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                vec![
+                    self.bidder.clone(),
+                    self.auction.clone(),
+                    self.escrow.clone(),
+                    self.bidder_token.clone(),
+                    self.bid_deposit.clone(),
+                    self.token_program.clone(),
+                ]
+            }
+        }
+    }
+}
@@ -0,0 +1,501 @@
+//! Off-chain RPC client
+//!
+//! Enabled by the `client` feature and never built for the BPF target. Wraps
+//! a nonblocking `RpcClient` with the fetch/decode, query and transaction
+//! plumbing every integrator otherwise rewrites.
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    client_error::ClientError as RpcError,
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::{
+    clock::Clock, instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, sysvar,
+};
+use solana_sdk::{
+    account::from_account,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use thiserror::Error;
+
+use crate::{
+    oracle::PriceFeed,
+    pda::{
+        find_auction_address, find_bid_delegate_address, find_bid_deposit_address,
+        find_bid_session_address, find_bidder_stats_address, find_bonding_curve_address,
+        find_clock_intent_address, find_clock_sale_address, find_co_listing_address,
+        find_collection_bid_address, find_dealer_index_address, find_dealer_index_page_address,
+        find_dealer_stats_address, find_dispute_address, find_dutch_oracle_address,
+        find_fee_override_address, find_harberger_listing_address, find_increment_table_address,
+        find_mint_stats_address, find_payout_splits_address, find_proceeds_vesting_address,
+        find_registry_page_address, find_series_address, find_settlement_swap_address,
+        find_unit_sale_address, find_usd_pricing_address,
+    },
+    state::{
+        calculate_dutch_price, Auction, AuctionSeries, BidDelegate, BidDeposit, BidSession,
+        BidderStats, BondingCurve, ClockIntent, ClockSale, CoListing, CollectionBid, DealerIndex,
+        DealerIndexPage, DealerStats, Dispute, DutchOracle, FeeOverride, HarbergerListing,
+        IncrementTable, MintStats, PayoutSplits, PodAccount, ProceedsVesting, ProgramAccount,
+        RegistryPage, SettlementSwap, UnitSale, UsdPricing, DEALER_INDEX_PAGE_LEN,
+    },
+};
+
+/// Errors returned by [`AuctionClient`]
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] RpcError),
+
+    #[error("Account {0} is not owned by the auction program")]
+    WrongOwner(Pubkey),
+
+    #[error("Account {0} does not exist")]
+    MissingAccount(Pubkey),
+
+    #[error("Account data could not be decoded: {0}")]
+    Decode(#[from] ProgramError),
+
+    #[error("Clock sysvar could not be decoded")]
+    InvalidClock,
+}
+
+/// Client-side result type
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// RPC helpers for one deployment of the auction program
+pub struct AuctionClient {
+    rpc: RpcClient,
+    program_id: Pubkey,
+}
+
+impl AuctionClient {
+    /// Wrap an RPC client for the program deployed at `program_id`
+    pub fn new(rpc: RpcClient, program_id: Pubkey) -> Self {
+        Self { rpc, program_id }
+    }
+
+    /// Wrap an RPC client for the deployment the crate was built for: the
+    /// `devnet` or `mainnet` feature's program ID, or the localnet one
+    pub fn for_cluster(rpc: RpcClient) -> Self {
+        Self::new(rpc, crate::id())
+    }
+
+    /// Underlying RPC client
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Program this client targets
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Fetch and decode the auction with `auction_id`
+    pub async fn get_auction(&self, auction_id: &[u8; 32]) -> Result<Auction> {
+        let (address, _) = find_auction_address(&self.program_id, auction_id);
+        self.get_auction_at(&address).await
+    }
+
+    /// Fetch and decode the auction stored at `address`
+    pub async fn get_auction_at(&self, address: &Pubkey) -> Result<Auction> {
+        let account = self.rpc.get_account(address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(*address));
+        }
+        Ok(Auction::load(&account.data)?)
+    }
+
+    /// List every auction created by `dealer`
+    ///
+    /// Accounts that fail to decode, such as auctions still in an older
+    /// layout, are skipped.
+    pub async fn get_dealer_auctions(&self, dealer: &Pubkey) -> Result<Vec<(Pubkey, Auction)>> {
+        self.get_auctions_matching(Auction::DEALER_OFFSET, dealer.as_ref())
+            .await
+    }
+
+    /// List every auction listed under `category`
+    ///
+    /// Accounts that fail to decode are skipped, as in
+    /// [`get_dealer_auctions`](Self::get_dealer_auctions).
+    pub async fn get_category_auctions(&self, category: u16) -> Result<Vec<(Pubkey, Auction)>> {
+        self.get_auctions_matching(Auction::CATEGORY_OFFSET, &category.to_le_bytes())
+            .await
+    }
+
+    /// Fetch auctions whose account data holds `bytes` at `offset`
+    async fn get_auctions_matching(
+        &self,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<Vec<(Pubkey, Auction)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    &<Auction as PodAccount>::DISCRIMINATOR,
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, bytes)),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                Auction::load(&account.data)
+                    .ok()
+                    .map(|auction| (address, auction))
+            })
+            .collect())
+    }
+
+    /// IDs of every auction created by `dealer`, in creation order, read from
+    /// the dealer's index pages (empty if the dealer has no auctions)
+    pub async fn get_dealer_auction_ids(&self, dealer: &Pubkey) -> Result<Vec<[u8; 32]>> {
+        let (index_address, _) = find_dealer_index_address(&self.program_id, dealer);
+        let Some(account) = self
+            .rpc
+            .get_multiple_accounts(&[index_address])
+            .await?
+            .remove(0)
+        else {
+            return Ok(Vec::new());
+        };
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(index_address));
+        }
+        let index = DealerIndex::load(&account.data)?;
+
+        let page_count = index.auction_count.div_ceil(DEALER_INDEX_PAGE_LEN as u64) as u32;
+        let addresses: Vec<Pubkey> = (0..page_count)
+            .map(|page| find_dealer_index_page_address(&self.program_id, dealer, page).0)
+            .collect();
+        let pages = self.rpc.get_multiple_accounts(&addresses).await?;
+
+        let mut ids = Vec::with_capacity(index.auction_count as usize);
+        for (address, page) in addresses.into_iter().zip(pages) {
+            let page = page.ok_or(ClientError::MissingAccount(address))?;
+            if page.owner != self.program_id {
+                return Err(ClientError::WrongOwner(address));
+            }
+            ids.extend_from_slice(DealerIndexPage::load(&page.data)?.auction_ids());
+        }
+        Ok(ids)
+    }
+
+    /// Fetch page `page` of the global auction registry
+    ///
+    /// Slot `i` holds the auction created with index `page * REGISTRY_PAGE_LEN + i`.
+    pub async fn get_registry_page(&self, page: u32) -> Result<RegistryPage> {
+        let (address, _) = find_registry_page_address(&self.program_id, page);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(RegistryPage::load(&account.data)?)
+    }
+
+    /// Fetch the protocol statistics for `payment_mint`
+    pub async fn get_mint_stats(&self, payment_mint: &Pubkey) -> Result<MintStats> {
+        let (address, _) = find_mint_stats_address(&self.program_id, payment_mint);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(MintStats::load(&account.data)?)
+    }
+
+    /// Fetch a dealer's track record
+    pub async fn get_dealer_stats(&self, dealer: &Pubkey) -> Result<DealerStats> {
+        let (address, _) = find_dealer_stats_address(&self.program_id, dealer);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(DealerStats::load(&account.data)?)
+    }
+
+    /// Fetch a bidder's totals, if they have opted into tracking
+    pub async fn get_bidder_stats(&self, bidder: &Pubkey) -> Result<BidderStats> {
+        let (address, _) = find_bidder_stats_address(&self.program_id, bidder);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(BidderStats::load(&account.data)?)
+    }
+
+    /// Fetch the payout splits of an auction
+    pub async fn get_payout_splits(&self, auction_id: &[u8; 32]) -> Result<PayoutSplits> {
+        let (address, _) = find_payout_splits_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(PayoutSplits::load(&account.data)?)
+    }
+
+    /// Fetch the depositors of a co-listed auction
+    pub async fn get_co_listing(&self, auction_id: &[u8; 32]) -> Result<CoListing> {
+        let (address, _) = find_co_listing_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(CoListing::load(&account.data)?)
+    }
+
+    /// Fetch `bidder`'s session on an auction
+    pub async fn get_bid_session(
+        &self,
+        auction_id: &[u8; 32],
+        bidder: &Pubkey,
+    ) -> Result<BidSession> {
+        let (address, _) = find_bid_session_address(&self.program_id, auction_id, bidder);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(BidSession::load(&account.data)?)
+    }
+
+    /// Fetch the USD start and reserve of an auction
+    pub async fn get_usd_pricing(&self, auction_id: &[u8; 32]) -> Result<UsdPricing> {
+        let (address, _) = find_usd_pricing_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(UsdPricing::load(&account.data)?)
+    }
+
+    /// Fetch the oracle start price configuration of a Dutch auction
+    pub async fn get_dutch_oracle(&self, auction_id: &[u8; 32]) -> Result<DutchOracle> {
+        let (address, _) = find_dutch_oracle_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(DutchOracle::load(&account.data)?)
+    }
+
+    /// Fetch the escrowed deposit of `bidder` on an auction
+    pub async fn get_bid_deposit(
+        &self,
+        auction_id: &[u8; 32],
+        bidder: &Pubkey,
+    ) -> Result<BidDeposit> {
+        let (address, _) = find_bid_deposit_address(&self.program_id, auction_id, bidder);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(BidDeposit::load(&account.data)?)
+    }
+
+    /// Fetch the vesting schedule of an auction's proceeds
+    pub async fn get_proceeds_vesting(&self, auction_id: &[u8; 32]) -> Result<ProceedsVesting> {
+        let (address, _) = find_proceeds_vesting_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(ProceedsVesting::load(&account.data)?)
+    }
+
+    /// Fetch the settlement swap of an auction's proceeds
+    pub async fn get_settlement_swap(&self, auction_id: &[u8; 32]) -> Result<SettlementSwap> {
+        let (address, _) = find_settlement_swap_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(SettlementSwap::load(&account.data)?)
+    }
+
+    /// Fetch an auction series
+    pub async fn get_series(&self, series_id: &[u8; 32]) -> Result<AuctionSeries> {
+        let (address, _) = find_series_address(&self.program_id, series_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(AuctionSeries::load(&account.data)?)
+    }
+
+    /// Fetch the clock sale of an auction
+    pub async fn get_clock_sale(&self, auction_id: &[u8; 32]) -> Result<ClockSale> {
+        let (address, _) = find_clock_sale_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(ClockSale::load(&account.data)?)
+    }
+
+    /// Fetch a buyer's intent in an auction's clock sale
+    pub async fn get_clock_intent(
+        &self,
+        auction_id: &[u8; 32],
+        buyer: &Pubkey,
+    ) -> Result<ClockIntent> {
+        let (address, _) = find_clock_intent_address(&self.program_id, auction_id, buyer);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(ClockIntent::load(&account.data)?)
+    }
+
+    /// Fetch the bonding curve of an auction
+    pub async fn get_bonding_curve(&self, auction_id: &[u8; 32]) -> Result<BondingCurve> {
+        let (address, _) = find_bonding_curve_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(BondingCurve::load(&account.data)?)
+    }
+
+    /// Fetch the unit sale of an auction
+    pub async fn get_unit_sale(&self, auction_id: &[u8; 32]) -> Result<UnitSale> {
+        let (address, _) = find_unit_sale_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(UnitSale::load(&account.data)?)
+    }
+
+    /// Fetch the fee override of an auction
+    pub async fn get_fee_override(&self, auction_id: &[u8; 32]) -> Result<FeeOverride> {
+        let (address, _) = find_fee_override_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(FeeOverride::load(&account.data)?)
+    }
+
+    /// Fetch the Harberger listing of an auction
+    pub async fn get_harberger_listing(&self, auction_id: &[u8; 32]) -> Result<HarbergerListing> {
+        let (address, _) = find_harberger_listing_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(HarbergerListing::load(&account.data)?)
+    }
+
+    /// Fetch a bidder's standing bid on a collection
+    pub async fn get_collection_bid(
+        &self,
+        collection: &Pubkey,
+        bidder: &Pubkey,
+    ) -> Result<CollectionBid> {
+        let (address, _) = find_collection_bid_address(&self.program_id, collection, bidder);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(CollectionBid::load(&account.data)?)
+    }
+
+    /// Fetch a bidder's bid delegate, whose nonce their next signed bid
+    /// must carry
+    pub async fn get_bid_delegate(&self, bidder: &Pubkey) -> Result<BidDelegate> {
+        let (address, _) = find_bid_delegate_address(&self.program_id, bidder);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(BidDelegate::load(&account.data)?)
+    }
+
+    /// Fetch the dispute over an auction's sale
+    pub async fn get_dispute(&self, auction_id: &[u8; 32]) -> Result<Dispute> {
+        let (address, _) = find_dispute_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(Dispute::load(&account.data)?)
+    }
+
+    /// Fetch the increment table of an auction
+    pub async fn get_increment_table(&self, auction_id: &[u8; 32]) -> Result<IncrementTable> {
+        let (address, _) = find_increment_table_address(&self.program_id, auction_id);
+        let account = self.rpc.get_account(&address).await?;
+        if account.owner != self.program_id {
+            return Err(ClientError::WrongOwner(address));
+        }
+        Ok(IncrementTable::load(&account.data)?)
+    }
+
+    /// Current cluster clock
+    pub async fn get_clock(&self) -> Result<Clock> {
+        let account = self.rpc.get_account(&sysvar::clock::id()).await?;
+        from_account(&account).ok_or(ClientError::InvalidClock)
+    }
+
+    /// Price a Dutch auction would charge at the cluster's current time
+    pub async fn get_dutch_price(&self, auction: &Auction) -> Result<u64> {
+        let mut params = *auction.dutch()?;
+        if auction.has_oracle_pricing {
+            let dutch_oracle = self.get_dutch_oracle(&auction.auction_id).await?;
+            let feed = self.rpc.get_account(&dutch_oracle.price_feed).await?;
+            params.start_price = dutch_oracle.start_price(&PriceFeed::parse(&feed.data)?)?;
+        }
+        let clock = self.get_clock().await?;
+        Ok(calculate_dutch_price(&params, clock.unix_timestamp))
+    }
+
+    /// Seconds of bidding an auction has left at the cluster's current time
+    pub async fn get_time_remaining(&self, auction: &Auction) -> Result<i64> {
+        let clock = self.get_clock().await?;
+        Ok(auction.time_remaining(clock.unix_timestamp)?)
+    }
+
+    /// Build a transaction paid for by `payer` and signed by `signers`
+    /// against the latest blockhash
+    pub async fn build_transaction(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<Transaction> {
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let mut all_signers: Vec<&dyn Signer> = vec![payer];
+        all_signers.extend(signers.iter().map(|signer| *signer as &dyn Signer));
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        ))
+    }
+
+    /// Build, sign and send a transaction, waiting for confirmation
+    pub async fn send(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let transaction = self.build_transaction(instructions, payer, signers).await?;
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+}
@@ -0,0 +1,532 @@
+//! Typed CPI into the auction program
+//!
+//! Each function builds its instruction with the matching
+//! [`instruction`] builder and invokes it, so on-chain
+//! programs (launchpads, games) can create auctions and bid without
+//! assembling instruction data. `accounts` must hold an `AccountInfo` for
+//! every account the builder lists, in any order; accounts the caller's
+//! program owns sign through `signer_seeds`. Builders extended with the
+//! `with_*` helpers are invoked directly with [`invoke`].
+//!
+//! Depend on the crate with the `cpi` feature to leave out its entrypoint.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, instruction::Instruction,
+    program::invoke_signed, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{
+    instruction::{self, CreateIndexes, DisputeRuling, EmergencyVault, GateAccounts},
+    state::{Auction, AuctionItem, AuctionTypeTag, IncrementTier, PayoutSplit},
+};
+
+/// Invoke `instruction` on `program`, passing the entries of `accounts` its
+/// account metas name
+pub fn invoke<'a>(
+    program: &AccountInfo<'a>,
+    instruction: Instruction,
+    accounts: &[AccountInfo<'a>],
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let mut infos = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            accounts
+                .iter()
+                .find(|account| *account.key == meta.pubkey)
+                .cloned()
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    infos.push(program.clone());
+    invoke_signed(&instruction, &infos, signer_seeds)
+}
+
+/// Define a CPI function per builder, taking the builder's arguments after
+/// `program_id`
+macro_rules! cpi_functions {
+    ($($(#[$doc:meta])* $name:ident($($arg:ident: $ty:ty),* $(,)?);)*) => {
+        $(
+            $(#[$doc])*
+            #[allow(clippy::too_many_arguments)]
+            pub fn $name<'a>(
+                program: &AccountInfo<'a>,
+                accounts: &[AccountInfo<'a>],
+                $($arg: $ty,)*
+                signer_seeds: &[&[&[u8]]],
+            ) -> ProgramResult {
+                let ix = instruction::$name(program.key, $($arg),*);
+                invoke(program, ix, accounts, signer_seeds)
+            }
+        )*
+    };
+}
+
+cpi_functions! {
+    /// Invoke an `Initialize` instruction
+    initialize(payer: &Pubkey);
+    /// Invoke a `Bootstrap` instruction creating the fee vaults of
+    /// `payment_mints`
+    bootstrap(
+        payer: &Pubkey,
+        curated: bool,
+        claim_window: i64,
+        acceptance_period: i64,
+        payment_mints: &[Pubkey],
+    );
+    /// Invoke a `SetPaused` instruction
+    set_paused(owner: &Pubkey, paused: bool);
+    /// Invoke a `TransferOwnership` instruction
+    transfer_ownership(owner: &Pubkey, new_owner: &Pubkey);
+    /// Invoke a `ClaimFees` instruction claiming `amount` of the fees, or all of
+    /// them if `None`
+    claim_fees(owner: &Pubkey, payment_mint: &Pubkey, owner_token: &Pubkey, amount: Option<u64>);
+    /// Invoke a `ClaimFeesBatch` instruction claiming the fees of each payment
+    /// mint into the paired owner token account
+    claim_fees_batch(owner: &Pubkey, claims: &[(Pubkey, Pubkey)]);
+    /// Invoke an `EmergencyWithdraw` instruction draining `vault` of `auction`
+    /// into `recovery_token`
+    emergency_withdraw(
+        owner: &Pubkey,
+        auction: &Auction,
+        vault: EmergencyVault,
+        recovery_token: &Pubkey,
+    );
+    /// Invoke a `SweepExpired` instruction paying the escrow rent to `caller`
+    sweep_expired(caller: &Pubkey, auction: &Auction);
+    /// Invoke a `CreateTraditionalAuction` instruction
+    create_traditional_auction(
+        dealer: &Pubkey,
+        payment_mint: &Pubkey,
+        indexes: &CreateIndexes,
+        auction_id: [u8; 32],
+        start_amount: u64,
+        increment: u64,
+        reserve_price: u64,
+        deadline: i64,
+        category: u16,
+        metadata_uri: &str,
+        payout_splits: &[PayoutSplit],
+        reserve_commitment: [u8; 32],
+    );
+    /// Invoke a `CreateDutchAuction` instruction
+    create_dutch_auction(
+        dealer: &Pubkey,
+        payment_mint: &Pubkey,
+        indexes: &CreateIndexes,
+        auction_id: [u8; 32],
+        start_price: u64,
+        decrease_amount: u64,
+        interval: i64,
+        minimum_price: u64,
+        deadline: i64,
+        category: u16,
+        metadata_uri: &str,
+        payout_splits: &[PayoutSplit],
+    );
+    /// Invoke a `CreatePennyAuction` instruction
+    create_penny_auction(
+        dealer: &Pubkey,
+        payment_mint: &Pubkey,
+        indexes: &CreateIndexes,
+        auction_id: [u8; 32],
+        increment: u64,
+        timer_duration: i64,
+        category: u16,
+        metadata_uri: &str,
+        payout_splits: &[PayoutSplit],
+    );
+    /// Invoke a `DepositTokens` instruction for the auction's next item slot
+    deposit_tokens(
+        depositor: &Pubkey,
+        auction: &Auction,
+        depositor_token: &Pubkey,
+        token_mint: &Pubkey,
+        amount: u64,
+        valuation: u64,
+    );
+    /// Invoke a `DepositNft` instruction for the auction's next item slot
+    deposit_nft(
+        depositor: &Pubkey,
+        auction: &Auction,
+        depositor_token: &Pubkey,
+        nft_mint: &Pubkey,
+        valuation: u64,
+    );
+    /// Invoke a `DepositSft` instruction for the auction's next item slot
+    deposit_sft(
+        depositor: &Pubkey,
+        auction: &Auction,
+        depositor_token: &Pubkey,
+        sft_mint: &Pubkey,
+        quantity: u64,
+        valuation: u64,
+    );
+    /// Invoke a `BidTraditional` instruction against the auction's current state
+    bid_traditional(
+        auction: &Auction,
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        previous_bidder_token: &Pubkey,
+        amount: u64,
+        memo: Option<&str>,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `BuyDutch` instruction against the auction's current state
+    buy_dutch(
+        auction: &Auction,
+        buyer: &Pubkey,
+        buyer_token: &Pubkey,
+        dealer_token: &Pubkey,
+        max_price: u64,
+        memo: Option<&str>,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `BidPenny` instruction against the auction's current state
+    bid_penny(
+        auction: &Auction,
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        dealer_token: &Pubkey,
+        memo: Option<&str>,
+        gates: &GateAccounts,
+    );
+    /// Invoke an `OpenBidSession` instruction
+    open_bid_session(
+        auction: &Auction,
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        session_key: &Pubkey,
+        expires_at: i64,
+        credits: u64,
+    );
+    /// Invoke a `BidPennySession` instruction against the auction's current state
+    bid_penny_session(
+        auction: &Auction,
+        bidder: &Pubkey,
+        session_key: &Pubkey,
+        dealer_token: &Pubkey,
+        memo: Option<&str>,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `CloseBidSession` instruction
+    close_bid_session(auction: &Auction, bidder: &Pubkey, bidder_token: &Pubkey);
+    /// Invoke a `FinalizeAuction` instruction
+    finalize_auction(
+        auction: &Auction,
+        caller: &Pubkey,
+        dealer_token: &Pubkey,
+        winner_token: &Pubkey,
+    );
+    /// Invoke an `AcceptBid` instruction signed by the auction's dealer
+    accept_bid(auction: &Auction, dealer_token: &Pubkey);
+    /// Invoke an `AcceptBid` instruction signed by `authority`, the dealer or
+    /// their delegate
+    accept_bid_as(auction: &Auction, authority: &Pubkey, dealer_token: &Pubkey);
+    /// Invoke a `RejectBid` instruction signed by the auction's dealer
+    reject_bid(auction: &Auction, winner_token: &Pubkey);
+    /// Invoke a `RejectBid` instruction signed by `authority`, the dealer or
+    /// their delegate
+    reject_bid_as(auction: &Auction, authority: &Pubkey, winner_token: &Pubkey);
+    /// Invoke a `CloseItemVault` instruction; `recipient` owns `recipient_token`
+    close_item_vault(
+        authority: &Pubkey,
+        auction: &Auction,
+        item_index: u8,
+        item_mint: &Pubkey,
+        recipient: &Pubkey,
+        recipient_token: &Pubkey,
+        rent_recipient: &Pubkey,
+    );
+    /// Invoke a `RelistAuction` instruction moving `items`, all of `auction`'s
+    /// items in index order, into `new_auction`
+    relist_auction(auction: &Auction, new_auction: &Auction, items: &[AuctionItem]);
+    /// Invoke a `CreateSeries` instruction
+    create_series(dealer: &Pubkey, series_id: &[u8; 32], lots: &[[u8; 32]]);
+    /// Invoke an `OpenNextLot` instruction
+    open_next_lot(
+        caller: &Pubkey,
+        series_id: &[u8; 32],
+        current_lot: &[u8; 32],
+        next_lot: &[u8; 32],
+    );
+    /// Invoke a `SetItemReserve` instruction
+    set_item_reserve(dealer: &Pubkey, auction_id: &[u8; 32], item_index: u8, reserve_price: u64);
+    /// Invoke a `SetItemRoyalty` instruction
+    set_item_royalty(
+        dealer: &Pubkey,
+        auction_id: &[u8; 32],
+        item_index: u8,
+        royalty_bps: u16,
+        beneficiary: &Pubkey,
+    );
+    /// Invoke a `SetClockSale` instruction
+    set_clock_sale(dealer: &Pubkey, auction_id: &[u8; 32], supply: u64);
+    /// Invoke a `RegisterIntent` instruction against the auction's current state
+    register_intent(
+        auction: &Auction,
+        buyer: &Pubkey,
+        buyer_token: &Pubkey,
+        quantity: u64,
+        max_price: u64,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `ClearClockSale` instruction against the auction's current state
+    clear_clock_sale(auction: &Auction, caller: &Pubkey, dealer_token: &Pubkey);
+    /// Invoke a `SettleIntent` instruction delivering `buyer`'s units of the
+    /// auction's `item_mint`
+    settle_intent(auction: &Auction, caller: &Pubkey, buyer: &Pubkey, item_mint: &Pubkey);
+    /// Invoke a `SetBondingCurve` instruction
+    set_bonding_curve(
+        dealer: &Pubkey,
+        auction_id: &[u8; 32],
+        supply: u64,
+        base_price: u64,
+        slope: u64,
+        exponent: u8,
+        sell_back: bool,
+    );
+    /// Invoke a `BuyCurve` instruction against the auction's current state
+    buy_curve(
+        auction: &Auction,
+        buyer: &Pubkey,
+        buyer_token: &Pubkey,
+        dealer_token: &Pubkey,
+        item_mint: &Pubkey,
+        quantity: u64,
+        max_cost: u64,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `SellCurve` instruction returning units of `item_mint` from the
+    /// seller's associated token account
+    sell_curve(
+        auction: &Auction,
+        seller: &Pubkey,
+        seller_token: &Pubkey,
+        item_mint: &Pubkey,
+        quantity: u64,
+        min_refund: u64,
+    );
+    /// Invoke a `CloseCurveSale` instruction against the auction's current state
+    close_curve_sale(auction: &Auction, caller: &Pubkey, dealer_token: &Pubkey);
+    /// Invoke a `SetUnitSale` instruction
+    set_unit_sale(dealer: &Pubkey, auction_id: &[u8; 32], supply: u64);
+    /// Invoke a `BuyUnits` instruction against the auction's current state
+    buy_units(
+        auction: &Auction,
+        buyer: &Pubkey,
+        buyer_token: &Pubkey,
+        dealer_token: &Pubkey,
+        item_mint: &Pubkey,
+        quantity: u64,
+        max_price: u64,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `CloseUnitSale` instruction
+    close_unit_sale(auction_id: &[u8; 32], caller: &Pubkey);
+    /// Invoke a `SetHarbergerListing` instruction
+    set_harberger_listing(
+        dealer: &Pubkey,
+        auction_id: &[u8; 32],
+        price: u64,
+        tax_bps: u16,
+        tax_period: i64,
+    );
+    /// Invoke a `SetAssessedPrice` instruction
+    set_assessed_price(
+        holder: &Pubkey,
+        auction_id: &[u8; 32],
+        holder_token: &Pubkey,
+        price: u64,
+        deposit: u64,
+    );
+    /// Invoke a `ForceBuy` instruction taking the item from `holder`, paid into
+    /// their associated token account
+    force_buy(
+        auction: &Auction,
+        buyer: &Pubkey,
+        buyer_token: &Pubkey,
+        holder: &Pubkey,
+        max_price: u64,
+        assessed_price: u64,
+        deposit: u64,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `CloseHarbergerListing` instruction for the listing's current
+    /// `holder`
+    close_harberger_listing(auction: &Auction, caller: &Pubkey, holder: &Pubkey);
+    /// Invoke a `PlaceCollectionBid` instruction
+    place_collection_bid(
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        payment_mint: &Pubkey,
+        collection: &Pubkey,
+        price: u64,
+        quantity: u64,
+        expires_at: i64,
+    );
+    /// Invoke a `CancelCollectionBid` instruction
+    cancel_collection_bid(
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        payment_mint: &Pubkey,
+        collection: &Pubkey,
+    );
+    /// Invoke an `AcceptCollectionBid` instruction selling the auction's NFT of
+    /// `item_mint` to `bidder`'s bid on `collection`, signed by `authority`,
+    /// the dealer or their delegate
+    accept_collection_bid(
+        auction: &Auction,
+        authority: &Pubkey,
+        item_mint: &Pubkey,
+        collection: &Pubkey,
+        bidder: &Pubkey,
+        dealer_token: &Pubkey,
+        min_price: u64,
+    );
+    /// Invoke a `SettleSignedBid` instruction submitting `bidder`'s order to bid
+    /// `amount` until `expires_at` with `nonce` against the auction's current
+    /// state
+    settle_signed_bid(
+        auction: &Auction,
+        submitter: &Pubkey,
+        bidder: &Pubkey,
+        bidder_token: &Pubkey,
+        previous_bidder_token: &Pubkey,
+        amount: u64,
+        expires_at: i64,
+        nonce: u64,
+        gates: &GateAccounts,
+    );
+    /// Invoke a `SetTypePaused` instruction
+    set_type_paused(owner: &Pubkey, auction_type: AuctionTypeTag, paused: bool);
+    /// Invoke a `FreezeAuction` or `UnfreezeAuction` instruction
+    set_frozen(owner: &Pubkey, auction_id: &[u8; 32], frozen: bool);
+    /// Invoke an `AddToBlacklist` instruction
+    add_to_blacklist(owner: &Pubkey, wallet: &Pubkey);
+    /// Invoke a `RemoveFromBlacklist` instruction
+    remove_from_blacklist(owner: &Pubkey, wallet: &Pubkey);
+    /// Invoke an `EnableFeeBurn` instruction
+    enable_fee_burn(owner: &Pubkey, payment_mint: &Pubkey);
+    /// Invoke a `DisableFeeBurn` instruction
+    disable_fee_burn(owner: &Pubkey, payment_mint: &Pubkey);
+    /// Invoke an `AddFeeExemption` instruction
+    add_fee_exemption(owner: &Pubkey, dealer: &Pubkey);
+    /// Invoke a `RemoveFeeExemption` instruction
+    remove_fee_exemption(owner: &Pubkey, dealer: &Pubkey);
+    /// Invoke a `SetFeeOverride` instruction
+    set_fee_override(owner: &Pubkey, auction_id: &[u8; 32], fee_bps: u16);
+    /// Invoke a `RemoveFeeOverride` instruction
+    remove_fee_override(owner: &Pubkey, auction_id: &[u8; 32]);
+    /// Invoke a `SetCuratedMode` instruction
+    set_curated_mode(owner: &Pubkey, curated: bool);
+    /// Invoke a `SetCreationPaused` instruction
+    set_creation_paused(owner: &Pubkey, paused: bool);
+    /// Invoke a `SetBiddingPaused` instruction
+    set_bidding_paused(owner: &Pubkey, paused: bool);
+    /// Invoke a `SetClaimWindow` instruction
+    set_claim_window(owner: &Pubkey, claim_window: i64);
+    /// Invoke a `SetAcceptancePeriod` instruction
+    set_acceptance_period(owner: &Pubkey, acceptance_period: i64);
+    /// Invoke a `RegisterDealer` instruction
+    register_dealer(dealer: &Pubkey);
+    /// Invoke a `SetDealerApproval` instruction
+    set_dealer_approval(owner: &Pubkey, dealer: &Pubkey, approved: bool);
+    /// Invoke a `SetBondRequirement` instruction
+    set_bond_requirement(owner: &Pubkey, payment_mint: &Pubkey, amount: u64);
+    /// Invoke a `PostBond` instruction signed by the auction's dealer
+    post_bond(auction: &Auction, dealer_token: &Pubkey);
+    /// Invoke a `WithdrawBond` instruction signed by the auction's dealer
+    withdraw_bond(auction: &Auction, dealer_token: &Pubkey);
+    /// Invoke a `SlashBond` instruction
+    slash_bond(owner: &Pubkey, auction: &Auction);
+    /// Invoke an `ApproveDepositor` instruction
+    approve_depositor(dealer: &Pubkey, auction_id: &[u8; 32], depositor: &Pubkey);
+    /// Invoke a `SetAuctionDelegate` instruction
+    set_auction_delegate(dealer: &Pubkey, auction_id: &[u8; 32], delegate: &Pubkey);
+    /// Invoke a `SetUsdPricing` instruction
+    set_usd_pricing(
+        dealer: &Pubkey,
+        auction: &Auction,
+        price_feed: &Pubkey,
+        reserve_usd_cents: u64,
+        start_usd_cents: u64,
+    );
+    /// Invoke a `SetDutchOracle` instruction
+    set_dutch_oracle(
+        dealer: &Pubkey,
+        auction: &Auction,
+        lot_mint: &Pubkey,
+        price_feed: &Pubkey,
+        premium_bps: u16,
+        lot_amount: u64,
+    );
+    /// Invoke a `RevealReserve` instruction
+    reveal_reserve(dealer: &Pubkey, auction_id: &[u8; 32], reserve_price: u64, salt: [u8; 32]);
+    /// Invoke an `UpdateReservePrice` instruction
+    update_reserve_price(dealer: &Pubkey, auction_id: &[u8; 32], reserve_price: u64);
+    /// Invoke a `SetBidIncrement` instruction
+    set_bid_increment(dealer: &Pubkey, auction_id: &[u8; 32], increment: u64, increment_bps: u16);
+    /// Invoke a `SetTickSize` instruction
+    set_tick_size(dealer: &Pubkey, auction_id: &[u8; 32], tick_size: u32);
+    /// Invoke a `SetIncrementTable` instruction
+    set_increment_table(dealer: &Pubkey, auction_id: &[u8; 32], tiers: &[IncrementTier]);
+    /// Invoke a `SetAllowlistRoot` instruction
+    set_allowlist_root(dealer: &Pubkey, auction_id: &[u8; 32], root: [u8; 32]);
+    /// Invoke a `ProveAllowlist` instruction
+    prove_allowlist(bidder: &Pubkey, auction_id: &[u8; 32], proof: Vec<[u8; 32]>);
+    /// Invoke an `UpdateMetadata` instruction
+    update_metadata(dealer: &Pubkey, auction_id: &[u8; 32], category: u16, metadata_uri: &str);
+    /// Invoke a `SetTokenGate` instruction
+    set_token_gate(dealer: &Pubkey, auction_id: &[u8; 32], mint: &Pubkey, min_balance: u64);
+    /// Invoke a `SetCollectionGate` instruction
+    set_collection_gate(dealer: &Pubkey, auction_id: &[u8; 32], collection: &Pubkey);
+    /// Invoke an `InitBidHistory` instruction
+    init_bid_history(dealer: &Pubkey, auction_id: &[u8; 32]);
+    /// Invoke a `WithdrawLosingBid` instruction
+    withdraw_losing_bid(auction: &Auction, bidder: &Pubkey, bidder_token: &Pubkey);
+    /// Invoke a `ClaimExpiredBidRefund` instruction for the auction's winning
+    /// bidder
+    claim_expired_bid_refund(auction: &Auction, bidder_token: &Pubkey);
+    /// Invoke a `SetArbiter` instruction
+    set_arbiter(dealer: &Pubkey, auction_id: &[u8; 32], arbiter: &Pubkey);
+    /// Invoke an `OpenDispute` instruction signed by the dealer or winner
+    open_dispute(party: &Pubkey, auction_id: &[u8; 32]);
+    /// Invoke a `ResolveDispute` instruction signed by the auction's arbiter
+    resolve_dispute(auction: &Auction, dealer_token: &Pubkey, ruling: DisputeRuling);
+    /// Invoke a `ReleaseProceeds` instruction
+    release_proceeds(auction: &Auction, caller: &Pubkey, dealer_token: &Pubkey);
+    /// Invoke a `SetDeliveryTimeout` instruction
+    set_delivery_timeout(dealer: &Pubkey, auction_id: &[u8; 32], timeout: u32);
+    /// Invoke a `ConfirmDelivery` instruction signed by the winner
+    confirm_delivery(auction: &Auction, dealer_token: &Pubkey);
+    /// Invoke a `SetCharity` instruction
+    set_charity(dealer: &Pubkey, auction_id: &[u8; 32], charity: &Pubkey, charity_bps: u16);
+    /// Invoke a `SetProceedsVesting` instruction
+    set_proceeds_vesting(dealer: &Pubkey, auction_id: &[u8; 32], duration: u32);
+    /// Invoke a `SetSettlementSwap` instruction
+    set_settlement_swap(
+        dealer: &Pubkey,
+        auction_id: &[u8; 32],
+        target_mint: &Pubkey,
+        max_slippage_bps: u16,
+    );
+    /// Invoke a `SwapProceeds` instruction signed by the dealer, forwarding the
+    /// Jupiter `route` instruction built for the auction's escrow
+    swap_proceeds(auction: &Auction, target_token: &Pubkey, route: Instruction, quoted_out: u64);
+    /// Invoke a `ClaimVested` instruction
+    claim_vested(auction: &Auction, caller: &Pubkey, dealer_token: &Pubkey);
+    /// Invoke a `CloseBidReceipt` instruction
+    close_bid_receipt(bidder: &Pubkey, auction_id: &[u8; 32], bid_index: u64);
+    /// Invoke a `MigrateAuction` instruction
+    migrate_auction(payer: &Pubkey, auction_id: &[u8; 32]);
+    /// Invoke an `UpgradeProgramState` instruction
+    upgrade_program_state(owner: &Pubkey);
+    /// Invoke a `GetVersion` instruction
+    get_version();
+    /// Invoke a `GetAuctionInfo` instruction
+    get_auction_info(auction_id: &[u8; 32]);
+    /// Invoke an `InitFeeVault` instruction
+    init_fee_vault(payer: &Pubkey, payment_mint: &Pubkey);
+}
@@ -1,10 +1,18 @@
 //! Program errors
 
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
 /// Auction program errors
-#[derive(Error, Debug, Copy, Clone)]
+///
+/// Discriminants are the `Custom(n)` codes seen by clients, so new variants
+/// must only ever be appended.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
 pub enum AuctionError {
     #[error("Only the owner can perform this action")]
     OnlyOwner,
@@ -77,6 +85,357 @@ pub enum AuctionError {
 
     #[error("Account already initialized")]
     AccountAlreadyInitialized,
+
+    #[error("Auction type is paused")]
+    AuctionTypePaused,
+
+    #[error("Auction is frozen")]
+    AuctionFrozen,
+
+    #[error("Wallet is blacklisted")]
+    Blacklisted,
+
+    #[error("Dealer is not approved")]
+    DealerNotApproved,
+
+    #[error("Dealer bond not posted")]
+    BondNotPosted,
+
+    #[error("Dealer bond already settled")]
+    BondSettled,
+
+    #[error("Bidder is not on the allowlist")]
+    NotAllowlisted,
+
+    #[error("Bidder does not hold the required gate tokens")]
+    TokenGateNotMet,
+
+    #[error("Bidder does not hold an NFT from the required collection")]
+    CollectionGateNotMet,
+
+    #[error("SPL Noop program account missing")]
+    NoopProgramMissing,
+
+    #[error("Account discriminator mismatch")]
+    InvalidAccountDiscriminator,
+
+    #[error("Token account has the wrong mint or owner")]
+    InvalidTokenAccount,
+
+    #[error("Unsupported account layout version")]
+    UnsupportedLayoutVersion,
+
+    #[error("Token account mint does not match the expected mint")]
+    TokenAccountMintMismatch,
+
+    #[error("Token account owner does not match the expected owner")]
+    TokenAccountOwnerMismatch,
+
+    #[error("Escrow account is not the auction's escrow PDA")]
+    InvalidEscrowAccount,
+
+    #[error("Fee vault token account is invalid")]
+    InvalidFeeVaultToken,
+
+    #[error("No fees available to withdraw")]
+    NoFeesAvailable,
+
+    #[error("Claim exceeds the accumulated fees")]
+    ClaimExceedsFees,
+
+    #[error("Item account does not belong to this auction")]
+    InvalidItemAccount,
+
+    #[error("Only the dealer or winner can perform this action")]
+    NotDealerOrWinner,
+
+    #[error("Memo exceeds the maximum length")]
+    MemoTooLong,
+
+    #[error("SPL Memo program account missing")]
+    MemoProgramMissing,
+
+    #[error("Metadata URI exceeds the maximum length")]
+    MetadataUriTooLong,
+
+    #[error("Auction already has bids")]
+    AuctionHasBids,
+
+    #[error("Contract is not paused")]
+    ContractNotPaused,
+
+    #[error("Invalid payout splits")]
+    InvalidPayoutSplits,
+
+    #[error("Payout split account missing")]
+    PayoutAccountMissing,
+
+    #[error("Depositor not approved by the dealer")]
+    DepositorNotApproved,
+
+    #[error("Too many depositors")]
+    TooManyDepositors,
+
+    #[error("Co-listing must be set up before items are deposited")]
+    CoListingAfterDeposit,
+
+    #[error("Mint already deposited into this co-listed auction")]
+    MintAlreadyDeposited,
+
+    #[error("Signer is not the session key")]
+    SessionKeyMismatch,
+
+    #[error("Bid session expired")]
+    SessionExpired,
+
+    #[error("Bid session spend cap exceeded")]
+    SessionSpendCapExceeded,
+
+    #[error("Invalid bid session expiry")]
+    InvalidSessionExpiry,
+
+    #[error("Price feed is not a trading Pyth price account")]
+    InvalidPriceFeed,
+
+    #[error("Price feed has not been updated recently")]
+    StalePrice,
+
+    #[error("Oracle pricing account missing")]
+    OraclePricingAccountMissing,
+
+    #[error("Auction has no hidden reserve")]
+    NoHiddenReserve,
+
+    #[error("Revealed reserve does not match the commitment")]
+    ReserveRevealMismatch,
+
+    #[error("Invalid increment table")]
+    InvalidIncrementTable,
+
+    #[error("Increment table account missing")]
+    IncrementTableAccountMissing,
+
+    #[error("Bid is not a multiple of the tick size")]
+    BidOffTick,
+
+    #[error("Bid deposit account missing")]
+    BidDepositAccountMissing,
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw,
+
+    #[error("Auction has no arbiter")]
+    NoArbiter,
+
+    #[error("Only the arbiter can perform this action")]
+    OnlyArbiter,
+
+    #[error("Auction is under dispute")]
+    AuctionDisputed,
+
+    #[error("Dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[error("Dispute window is still open")]
+    DisputeWindowOpen,
+
+    #[error("No proceeds held in escrow")]
+    ProceedsNotHeld,
+
+    #[error("No open dispute")]
+    NoOpenDispute,
+
+    #[error("Delivery confirmation window is still open")]
+    DeliveryWindowOpen,
+
+    #[error("Only the winner can perform this action")]
+    OnlyWinner,
+
+    #[error("Proceeds vesting account missing")]
+    ProceedsVestingAccountMissing,
+
+    #[error("Fee burn account missing")]
+    FeeBurnAccountMissing,
+
+    #[error("Fee exemption belongs to another dealer")]
+    InvalidFeeExemption,
+
+    #[error("Auction has no settlement swap")]
+    NoSettlementSwap,
+
+    #[error("Vested proceeds cannot be swapped at settlement")]
+    SwapWithVesting,
+
+    #[error("Swap program is not Jupiter")]
+    InvalidSwapProgram,
+
+    #[error("Swap did not spend exactly the dealer's proceeds")]
+    SwapAmountMismatch,
+
+    #[error("Swap output is below the slippage bound")]
+    SlippageExceeded,
+
+    #[error("Auction was not refunded")]
+    AuctionNotRefunded,
+
+    #[error("Co-listed auctions cannot be relisted")]
+    CoListedRelist,
+
+    #[error("Relisted items must go to a new auction without items or bids")]
+    RelistTargetNotEmpty,
+
+    #[error("Invalid auction series")]
+    InvalidSeries,
+
+    #[error("Series lot is not open yet")]
+    LotNotOpen,
+
+    #[error("Current series lot has not settled")]
+    LotNotSettled,
+
+    #[error("Clock sales need a Dutch auction of one item, priced without an oracle and paid out at once")]
+    ClockSaleUnsupported,
+
+    #[error("Clock supply must evenly divide the item amount")]
+    InvalidClockSupply,
+
+    #[error("Clock sales take intents and settle through ClearClockSale")]
+    AuctionIsClockSale,
+
+    #[error("Auction is not a clock sale")]
+    NotClockSale,
+
+    #[error("Demand already covers the clock sale's supply")]
+    ClockSaleCovered,
+
+    #[error("Clock sale has not cleared")]
+    ClockSaleNotCleared,
+
+    #[error("Curve sales need a Dutch auction of one item, priced without an oracle and paid out at once")]
+    CurveSaleUnsupported,
+
+    #[error("Curve supply must evenly divide the item amount, with an exponent of at most 3")]
+    InvalidBondingCurve,
+
+    #[error("Curve sales trade through BuyCurve and SellCurve")]
+    AuctionIsCurveSale,
+
+    #[error("Auction is not a curve sale")]
+    NotCurveSale,
+
+    #[error("Curve sale does not buy units back")]
+    SellBackDisabled,
+
+    #[error("Harberger listings need a Dutch auction of one item, priced without an oracle and paid out at once")]
+    HarbergerUnsupported,
+
+    #[error("Harberger listings need a price and a tax period, with a tax rate of at most 100%")]
+    InvalidHarbergerListing,
+
+    #[error("Harberger listings trade through ForceBuy")]
+    AuctionIsHarbergerListing,
+
+    #[error("Auction is not a Harberger listing")]
+    NotHarbergerListing,
+
+    #[error("Only the listing's holder can reassess it")]
+    OnlyHolder,
+
+    #[error("Holder's tax deposit has run out")]
+    HarbergerForeclosed,
+
+    #[error("Collection bids need a price, a quantity and an expiry in the future")]
+    InvalidCollectionBid,
+
+    #[error("Collection bid has expired")]
+    CollectionBidExpired,
+
+    #[error("Collection bid has bought every NFT it asked for")]
+    CollectionBidFilled,
+
+    #[error("Auction does not sell a single NFT of the bid's collection")]
+    NotInBidCollection,
+
+    #[error("Collection bid is below the item's reserve")]
+    CollectionBidBelowReserve,
+
+    #[error("Signed bid has no matching Ed25519 signature in the transaction")]
+    InvalidSignedBid,
+
+    #[error("Signed bid has expired")]
+    SignedBidExpired,
+
+    #[error("Acceptance period outside the allowed bounds")]
+    AcceptancePeriodOutOfBounds,
+
+    #[error("Penny timer duration outside the allowed bounds")]
+    PennyTimerOutOfBounds,
+
+    #[error("Reserve price met; finalize the auction instead")]
+    ReservePriceMet,
+
+    #[error("Item delivery account missing")]
+    ItemAccountMissing,
+
+    #[error("Unit sales need a Dutch auction of one item, priced without an oracle and paid out at once")]
+    UnitSaleUnsupported,
+
+    #[error("Unit supply must evenly divide the item amount")]
+    InvalidUnitSupply,
+
+    #[error("Unit sales trade through BuyUnits")]
+    AuctionIsUnitSale,
+
+    #[error("Auction is not a unit sale")]
+    NotUnitSale,
+
+    #[error("Fee override above 100%")]
+    InvalidFeeOverride,
+
+    #[error("Fee override account missing")]
+    FeeOverrideAccountMissing,
+
+    #[error("Program account does not match the expected program")]
+    InvalidProgramAccount,
+
+    #[error("The same account was passed for distinct roles")]
+    DuplicateAccount,
+
+    #[error("Amount must be greater than zero")]
+    InvalidAmount,
+
+    #[error("Invalid auction parameter")]
+    InvalidParameter,
+
+    #[error("NFT mint must have 0 decimals and a supply of 1")]
+    InvalidNftMint,
+
+    #[error("SFT mint must have 0 decimals and a supply above 1")]
+    InvalidSftMint,
+
+    #[error("Metadata account missing")]
+    MetadataAccountMissing,
+
+    #[error("Invalid item royalty")]
+    InvalidRoyalty,
+
+    #[error("Item royalties need proceeds paid out at once")]
+    RoyaltyUnsupported,
+
+    #[error("Settlement swap target is the payment mint")]
+    SwapIntoPaymentMint,
+
+    #[error("Auction creation is paused")]
+    CreationPaused,
+
+    #[error("Bidding is paused")]
+    BiddingPaused,
+
+    #[error("Fee exemption account missing")]
+    FeeExemptionAccountMissing,
+
+    #[error("Signed bid nonce is not the bidder's next")]
+    InvalidSignedBidNonce,
 }
 
 impl From<AuctionError> for ProgramError {
@@ -84,3 +443,22 @@ impl From<AuctionError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for AuctionError {
+    fn type_of() -> &'static str {
+        "AuctionError"
+    }
+}
+
+impl PrintProgramError for AuctionError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
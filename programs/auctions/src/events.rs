@@ -0,0 +1,89 @@
+//! Structured program events
+//!
+//! Handlers log human-readable messages with `msg!`. With the `noop-events`
+//! feature, the events below are additionally CPI'd to the SPL Noop program
+//! so indexers can read them from instruction data, which is not subject to
+//! log truncation.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey, pubkey::Pubkey,
+};
+
+use crate::state::{AuctionStatus, AuctionTypeTag};
+
+/// SPL Noop program ID
+pub const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Events emitted by the auction program
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AuctionEvent {
+    /// A new auction was created
+    AuctionCreated {
+        auction_id: [u8; 32],
+        dealer: Pubkey,
+        auction_type: AuctionTypeTag,
+        payment_mint: Pubkey,
+    },
+    /// A bid (or Dutch purchase) was accepted
+    BidPlaced {
+        auction_id: [u8; 32],
+        bidder: Pubkey,
+        amount: u64,
+        timestamp: i64,
+        /// Memo attached by the bidder, also forwarded to SPL Memo
+        memo: Option<String>,
+    },
+    /// An auction reached a settled or expired state
+    AuctionSettled {
+        auction_id: [u8; 32],
+        status: AuctionStatus,
+        winner: Pubkey,
+        amount: u64,
+        timestamp: i64,
+    },
+    /// A charity's share of an auction's proceeds was paid out
+    CharityPaid {
+        auction_id: [u8; 32],
+        charity: Pubkey,
+        amount: u64,
+        timestamp: i64,
+    },
+    /// The owner drained one of an auction's vaults while paused
+    EmergencyWithdrawal {
+        auction_id: [u8; 32],
+        vault: Pubkey,
+        recovery: Pubkey,
+        amount: u64,
+        timestamp: i64,
+    },
+}
+
+/// Emit an event through the SPL Noop program when `noop-events` is enabled.
+///
+/// The Noop program account may appear anywhere in `accounts`; it is required
+/// when the feature is on so events are never silently dropped.
+#[cfg(feature = "noop-events")]
+pub fn emit(accounts: &[AccountInfo], event: &AuctionEvent) -> ProgramResult {
+    use solana_program::{instruction::Instruction, program::invoke};
+
+    let noop_program = accounts
+        .iter()
+        .find(|account| *account.key == NOOP_PROGRAM_ID)
+        .ok_or(crate::error::AuctionError::NoopProgramMissing)?;
+
+    invoke(
+        &Instruction {
+            program_id: NOOP_PROGRAM_ID,
+            accounts: vec![],
+            data: borsh::to_vec(event)?,
+        },
+        std::slice::from_ref(noop_program),
+    )
+}
+
+/// Emit an event through the SPL Noop program when `noop-events` is enabled.
+#[cfg(not(feature = "noop-events"))]
+pub fn emit(_accounts: &[AccountInfo], _event: &AuctionEvent) -> ProgramResult {
+    Ok(())
+}
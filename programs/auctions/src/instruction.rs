@@ -1,9 +1,43 @@
-//! Program instructions
+//! Program instructions and client-side builders
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{
+    memo::MEMO_PROGRAM_ID,
+    metadata::find_metadata_address,
+    pda::{
+        find_allowlist_pass_address, find_auction_address, find_bid_delegate_address,
+        find_bid_deposit_address, find_bid_history_address, find_bid_receipt_address,
+        find_bid_session_address, find_bidder_stats_address, find_blacklist_address,
+        find_bond_address, find_bond_config_address, find_bond_vault_address,
+        find_bonding_curve_address, find_clock_intent_address, find_clock_sale_address,
+        find_co_listing_address, find_collection_bid_address, find_dealer_index_address,
+        find_dealer_index_page_address, find_dealer_profile_address, find_dealer_stats_address,
+        find_dispute_address, find_dutch_oracle_address, find_escrow_address,
+        find_fee_burn_address, find_fee_exemption_address, find_fee_override_address,
+        find_fee_vault_address, find_fee_vault_token_address, find_harberger_listing_address,
+        find_increment_table_address, find_item_address, find_item_vault_address,
+        find_mint_stats_address, find_payout_splits_address, find_proceeds_vesting_address,
+        find_program_state_address, find_registry_page_address, find_series_address,
+        find_settlement_swap_address, find_unit_sale_address, find_usd_pricing_address,
+    },
+    state::{
+        Auction, AuctionItem, AuctionTypeTag, CoListing, DealerIndex, DutchOracle, IncrementTier,
+        PayoutSplit, PayoutSplits, RegistryPage, UsdPricing,
+    },
+    swap::JUPITER_PROGRAM_ID,
+};
 
 /// Auction program instructions
+///
+/// Signer accounts only need `is_signer`, so a dealer may be a PDA signing
+/// through `invoke_signed` (a DAO treasury, say). A dealer that pays for
+/// created accounts must be system-owned and hold no data.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum AuctionInstruction {
     // ============ Program Management ============
@@ -26,15 +60,17 @@ pub enum AuctionInstruction {
     /// 1. `[writable]` Program state PDA
     TransferOwnership { new_owner: Pubkey },
 
-    /// Claim accumulated fees for a specific payment token
+    /// Claim accumulated fees for a specific payment token: `amount` of them,
+    /// leaving the rest in the vault, or all of them if `None`
     /// Accounts:
     /// 0. `[signer]` Owner
     /// 1. `[]` Program state PDA
     /// 2. `[writable]` Fee vault PDA
     /// 3. `[writable]` Fee vault token account
     /// 4. `[writable]` Owner token account
-    /// 5. `[]` Token program
-    ClaimFees,
+    /// 5. `[]` Payment mint
+    /// 6. `[]` Token program
+    ClaimFees { amount: Option<u64> },
 
     // ============ Auction Creation ============
     /// Create a traditional auction
@@ -46,12 +82,33 @@ pub enum AuctionInstruction {
     /// 4. `[]` Payment mint
     /// 5. `[]` Token program
     /// 6. `[]` System program
+    /// 7. `[]` Dealer blacklist PDA
+    /// 8. `[]` Dealer profile PDA (checked in curated mode)
+    /// 9. `[]` Bond config PDA for the payment mint
+    /// 10. `[writable]` Dealer index PDA (created on the dealer's first auction)
+    /// 11. `[writable]` Dealer index page PDA for the dealer's next auction
+    /// 12. `[writable]` Registry page PDA for the program's next auction
+    /// 13. `[writable]` Dealer stats PDA (created if missing)
+    /// 14. `[writable]` Payout splits PDA (only if `payout_splits` is non-empty)
+    /// 15. `[]` Fee exemption PDA for the dealer (recorded if it exists)
+    ///
+    /// `category` is a marketplace-defined vertical (0 if uncategorized).
+    /// `metadata_uri` points at the listing's off-chain JSON and may be
+    /// empty; it is capped at `MAX_METADATA_URI_LEN` bytes. `payout_splits`
+    /// shares the proceeds after fees with up to `MAX_PAYOUT_SPLITS` other
+    /// wallets; the dealer receives the remainder. A non-zero
+    /// `reserve_commitment` (see `hash_reserve`) hides the reserve until
+    /// `RevealReserve`, and `reserve_price` must then be 0.
     CreateTraditionalAuction {
         auction_id: [u8; 32],
         start_amount: u64,
         increment: u64,
         reserve_price: u64,
         deadline: i64,
+        category: u16,
+        metadata_uri: String,
+        payout_splits: Vec<PayoutSplit>,
+        reserve_commitment: [u8; 32],
     },
 
     /// Create a Dutch auction
@@ -63,98 +120,4526 @@ pub enum AuctionInstruction {
         interval: i64,
         minimum_price: u64,
         deadline: i64,
+        category: u16,
+        metadata_uri: String,
+        payout_splits: Vec<PayoutSplit>,
     },
 
-    /// Create a Penny auction
+    /// Create a Penny auction. A `timer_duration` of zero uses
+    /// `PENNY_TIMER_DURATION`; otherwise it must lie within
+    /// `MIN_PENNY_TIMER_DURATION..=MAX_PENNY_TIMER_DURATION`.
     /// Accounts: (same as Traditional)
     CreatePennyAuction {
         auction_id: [u8; 32],
         increment: u64,
         timer_duration: i64,
+        category: u16,
+        metadata_uri: String,
+        payout_splits: Vec<PayoutSplit>,
     },
 
     // ============ Item Management ============
     /// Deposit SPL tokens into auction
     /// Accounts:
-    /// 0. `[signer]` Dealer
+    /// 0. `[signer]` Depositor (the dealer, their delegate, or one approved
+    ///    through `ApproveDepositor`)
     /// 1. `[writable]` Auction account
     /// 2. `[writable]` Item account PDA
-    /// 3. `[writable]` Dealer token account
+    /// 3. `[writable]` Depositor token account
     /// 4. `[writable]` Item vault token account PDA
     /// 5. `[]` Token mint
     /// 6. `[]` Token program
     /// 7. `[]` System program
-    DepositTokens { amount: u64 },
+    /// 8. `[]` Depositor blacklist PDA
+    /// 9. `[writable]` Co-listing PDA (only for co-listed auctions)
+    ///
+    /// `valuation` is the item's declared value, which weights the
+    /// depositor's share of a co-listed auction's proceeds. A mint may be
+    /// deposited more than once, each item in a vault of its own; auctions
+    /// migrated from layouts keeping one vault per mint take no repeat
+    /// deposits once co-listed.
+    DepositTokens { amount: u64, valuation: u64 },
 
     /// Deposit NFT into auction (Metaplex)
-    /// Accounts: (same as DepositTokens + metadata account)
-    DepositNft,
+    /// Accounts: (same as DepositTokens)
+    ///
+    /// The mint must have 0 decimals and a supply of 1. Any deposit passing
+    /// the mint's metadata account after its accounts records the metadata's
+    /// royalty on the item.
+    DepositNft { valuation: u64 },
 
     // ============ Bidding ============
     /// Place bid on Traditional auction
     /// Accounts:
-    /// 0. `[signer]` Bidder
+    /// 0. `[signer, writable]` Bidder
     /// 1. `[writable]` Auction account
     /// 2. `[writable]` Escrow token account
     /// 3. `[writable]` Bidder token account
-    /// 4. `[writable]` Previous bidder token account (for refund)
+    /// 4. `[writable]` Previous bidder token account (refunds bids placed
+    ///    before deposits were tracked)
     /// 5. `[]` Program state
     /// 6. `[]` Token program
-    /// 7. `[]` Clock sysvar
-    BidTraditional { amount: u64 },
+    /// 7. `[writable]` Bid receipt PDA
+    /// 8. `[]` System program
+    /// 9. `[]` Previous bidder (owner of the refund account, created if missing)
+    /// 10. `[]` Payment mint
+    /// 11. `[]` Associated token program
+    /// 12. `[]` Allowlist pass PDA (only if the auction has an allowlist)
+    /// 13. `[]` Bidder gate token account (only if the auction is token-gated)
+    /// 14. `[]` Bidder collection NFT token account (only if collection-gated)
+    /// 15. `[]` Collection NFT metadata account (only if collection-gated)
+    /// 16. `[writable]` Bid history PDA (only if the auction keeps a bid history)
+    ///
+    /// An optional `memo` (at most `MAX_MEMO_LEN` bytes) is forwarded to SPL
+    /// Memo; the Memo program account must then be passed after the accounts
+    /// above.
+    ///
+    /// The bidder's stats PDA may also be passed anywhere after the accounts
+    /// above, writable, to opt into tracking their totals (see
+    /// `with_bidder_stats`); it is created at the signer's expense.
+    ///
+    /// The bidder's deposit PDA (writable, created at their expense) and the
+    /// previous bidder's must also be passed anywhere after the accounts
+    /// above. Outbid funds stay in escrow until withdrawn with
+    /// `WithdrawLosingBid`.
+    ///
+    /// Auctions priced in USD also need the USD pricing PDA and its price
+    /// feed anywhere after the accounts above (see `with_usd_pricing`), and
+    /// auctions with tiered increments their increment table PDA (see
+    /// `with_increment_table`).
+    BidTraditional { amount: u64, memo: Option<String> },
 
     /// Buy at current price in Dutch auction
     /// Accounts:
-    /// 0. `[signer]` Buyer
+    /// 0. `[signer, writable]` Buyer
     /// 1. `[writable]` Auction account
     /// 2. `[writable]` Buyer token account
     /// 3. `[writable]` Dealer token account
     /// 4. `[writable]` Fee vault token account
-    /// 5. `[writable]` Item vault(s)
-    /// 6. `[writable]` Buyer item account(s)
-    /// 7. `[]` Program state
-    /// 8. `[]` Token program
-    /// 9. `[]` Clock sysvar
-    BuyDutch { max_price: u64 },
+    /// 5. `[writable]` Fee vault PDA (created with `InitFeeVault`)
+    /// 6. `[]` Program state
+    /// 7. `[]` Token program
+    /// 8. `[]` System program
+    /// 9. `[writable]` Bid receipt PDA
+    /// 10. `[]` Dealer (owner of the payout account, created if missing)
+    /// 11. `[]` Payment mint
+    /// 12. `[]` Associated token program
+    /// 13. `[writable]` Mint stats PDA (created if missing)
+    /// 14. `[writable]` Dealer stats PDA (created if missing)
+    /// 15. `[]` Allowlist pass PDA (only if the auction has an allowlist)
+    /// 16. `[]` Buyer gate token account (only if the auction is token-gated)
+    /// 17. `[]` Buyer collection NFT token account (only if collection-gated)
+    /// 18. `[]` Collection NFT metadata account (only if collection-gated)
+    /// 19. `[writable]` Bid history PDA (only if the auction keeps a bid history)
+    ///
+    /// `memo` and the bidder stats PDA are handled as for `BidTraditional`.
+    /// For auctions with payout splits, the payout splits PDA and, for each
+    /// recipient, their associated token account for the payment mint and
+    /// the recipient must also be passed anywhere after the accounts above
+    /// (see `with_payout_splits`). A missing token account is created at the
+    /// signer's expense, which also takes the payment mint, system program
+    /// and associated token program.
+    /// Co-listed auctions likewise need the co-listing PDA and each
+    /// co-depositor's associated token account and the co-depositor (see
+    /// `with_co_listing`).
+    /// Auctions with an oracle-derived start price need the Dutch oracle PDA
+    /// and its price feed (see `with_dutch_oracle`).
+    ///
+    /// Items are delivered with the payment when, for each, the writable item
+    /// PDA, its vault, the buyer's associated token account for the item mint
+    /// (created if missing) and the mint are passed anywhere after the
+    /// accounts above (see `with_item_delivery`). Items left out, or whose own
+    /// reserve was not met, are claimed with `CloseItemVault`.
+    BuyDutch {
+        max_price: u64,
+        memo: Option<String>,
+    },
 
     /// Place bid on Penny auction
+    /// Accounts: (same as BuyDutch, with the bidder in place of the buyer)
+    BidPenny { memo: Option<String> },
+
+    // ============ Finalization ============
+    /// Finalize auction (permissionless when conditions met). Missing dealer
+    /// or winner associated token accounts are created at the caller's expense.
+    /// Token accounts paid out to must hold the payment mint and belong to the
+    /// auction's dealer or winning bidder.
     /// Accounts:
-    /// 0. `[signer]` Bidder
+    /// 0. `[signer, writable]` Caller
     /// 1. `[writable]` Auction account
-    /// 2. `[writable]` Bidder token account
+    /// 2. `[writable]` Escrow token account
     /// 3. `[writable]` Dealer token account
-    /// 4. `[writable]` Fee vault token account
-    /// 5. `[]` Program state
-    /// 6. `[]` Token program
-    /// 7. `[]` Clock sysvar
-    BidPenny,
-
-    // ============ Finalization ============
-    /// Finalize auction (permissionless when conditions met)
-    /// Accounts vary by auction type and state
+    /// 4. `[writable]` Winning bidder token account (refund if reserve unmet)
+    /// 5. `[writable]` Fee vault token account
+    /// 6. `[writable]` Fee vault PDA
+    /// 7. `[]` Program state
+    /// 8. `[]` Token program
+    /// 9. `[]` System program
+    /// 10. `[]` Dealer
+    /// 11. `[]` Winning bidder
+    /// 12. `[]` Payment mint
+    /// 13. `[]` Associated token program
+    /// 14. `[writable]` Mint stats PDA (created if missing)
+    /// 15. `[writable]` Dealer stats PDA (created if missing)
+    ///
+    /// The winner's stats PDA is optional, as for `BidTraditional`. Payout
+    /// split accounts are passed as for `BuyDutch`, and USD pricing accounts
+    /// as for `BidTraditional`; a USD reserve is re-checked at the current
+    /// price unless the feed has gone stale. The winner's deposit PDA must
+    /// be passed anywhere after the accounts above; a refunded winning bid
+    /// stays in escrow for `WithdrawLosingBid`.
     FinalizeAuction,
 
-    /// Dealer accepts bid below reserve (Traditional only)
+    /// Dealer accepts bid below reserve (Traditional only). An auction past
+    /// its deadline can be accepted while still Active, within the acceptance
+    /// period, without a `FinalizeAuction` first.
     /// Accounts:
-    /// 0. `[signer]` Dealer
+    /// 0. `[signer, writable]` Dealer or their delegate
     /// 1. `[writable]` Auction account
     /// 2. `[writable]` Escrow token account
     /// 3. `[writable]` Dealer token account
     /// 4. `[writable]` Fee vault token account
-    /// 5. `[writable]` Item vault(s)
-    /// 6. `[writable]` Winner item account(s)
-    /// 7. `[]` Program state
-    /// 8. `[]` Token program
-    /// 9. `[]` Clock sysvar
+    /// 5. `[writable]` Fee vault PDA
+    /// 6. `[]` Program state
+    /// 7. `[]` Token program
+    /// 8. `[writable]` Mint stats PDA (created if missing)
+    /// 9. `[]` System program
+    /// 10. `[writable]` Dealer stats PDA (created if missing)
+    ///
+    /// The winner's stats PDA is optional, as for `BidTraditional`. Payout
+    /// split accounts are passed as for `BuyDutch`.
     AcceptBid,
 
     // ============ Cleanup ============
     /// Close item vault and recover rent (after finalization)
     /// Accounts:
-    /// 0. `[signer]` Dealer or auction payer
-    /// 1. `[writable]` Item account
-    /// 2. `[writable]` Item vault token account
-    /// 3. `[writable]` Rent recipient
-    /// 4. `[]` Token program
+    /// 0. `[signer, writable]` Dealer or winner
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Item account
+    /// 3. `[writable]` Item vault token account
+    /// 4. `[writable]` Recipient token account (winner's if sold, else dealer's)
+    /// 5. `[writable]` Rent recipient
+    /// 6. `[]` Token program
+    /// 7. `[]` Recipient (owner of the recipient token account, created if missing)
+    /// 8. `[]` Item mint
+    /// 9. `[]` System program
+    /// 10. `[]` Associated token program
+    /// 11. `[]` Program state PDA
+    ///
+    /// Once the program's claim window has passed since finalization, a
+    /// dealer closing the vault of a sold item reclaims it; the payment
+    /// stays with the dealer as the sale completed.
     CloseItemVault { item_index: u8 },
+
+    // ============ Program Management (continued) ============
+    /// Pause/unpause creation and bidding for a single auction type without
+    /// affecting the others. Its auctions can still be finalized and settled.
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetTypePaused {
+        auction_type: AuctionTypeTag,
+        paused: bool,
+    },
+
+    /// Freeze a single auction, blocking bids and finalization
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Auction account
+    FreezeAuction,
+
+    /// Lift a freeze placed by `FreezeAuction`
+    /// Accounts: (same as FreezeAuction)
+    UnfreezeAuction,
+
+    /// Bar a wallet from creating auctions or depositing items
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Blacklist PDA for `wallet`
+    /// 3. `[]` System program
+    AddToBlacklist { wallet: Pubkey },
+
+    /// Remove a wallet from the blacklist, returning rent to the owner
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Blacklist PDA for `wallet`
+    RemoveFromBlacklist { wallet: Pubkey },
+
+    // ============ Dealer Registry ============
+    /// Toggle curated mode, requiring approved dealer profiles to create auctions
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetCuratedMode { curated: bool },
+
+    /// Register a dealer profile pending owner approval
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Dealer profile PDA
+    /// 2. `[]` System program
+    RegisterDealer,
+
+    /// Approve or revoke a registered dealer
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Dealer profile PDA
+    SetDealerApproval { approved: bool },
+
+    // ============ Dealer Bonds ============
+    /// Set the bond dealers must post per auction for a payment mint (0 disables)
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Bond config PDA
+    /// 3. `[]` Payment mint
+    /// 4. `[]` System program
+    SetBondRequirement { amount: u64 },
+
+    /// Post the bond required by an auction
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Dealer bond PDA
+    /// 3. `[writable]` Bond vault token account PDA
+    /// 4. `[writable]` Dealer token account
+    /// 5. `[]` Payment mint
+    /// 6. `[]` Token program
+    /// 7. `[]` System program
+    PostBond,
+
+    /// Return the bond to the dealer once the auction has settled
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Dealer bond PDA
+    /// 3. `[writable]` Bond vault token account PDA
+    /// 4. `[writable]` Dealer token account
+    /// 5. `[]` Token program
+    WithdrawBond,
+
+    /// Slash the bond into the fee vault, counting a dispute against the dealer
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[]` Auction account
+    /// 3. `[writable]` Dealer bond PDA
+    /// 4. `[writable]` Bond vault token account PDA
+    /// 5. `[writable]` Fee vault PDA
+    /// 6. `[writable]` Fee vault token account
+    /// 7. `[]` Token program
+    /// 8. `[writable]` Dealer stats PDA (created if missing)
+    /// 9. `[]` System program
+    SlashBond,
+
+    // ============ Bidder Gating ============
+    /// Restrict bidding to wallets under a Merkle root (all zeros clears it).
+    /// Only allowed before the first bid.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetAllowlistRoot { root: [u8; 32] },
+
+    /// Prove allowlist membership once, creating a pass checked on every bid
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Allowlist pass PDA
+    /// 3. `[]` System program
+    ProveAllowlist { proof: Vec<[u8; 32]> },
+
+    /// Require bidders to hold at least `min_balance` of `mint` (default
+    /// pubkey clears it). Only allowed before the first bid.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetTokenGate { mint: Pubkey, min_balance: u64 },
+
+    /// Require bidders to hold an NFT from a verified Metaplex collection
+    /// (default pubkey clears it). Only allowed before the first bid.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetCollectionGate { collection: Pubkey },
+
+    // ============ Bid History ============
+    /// Create the bid history ring buffer; every later bid must then pass it
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Bid history PDA
+    /// 3. `[]` System program
+    InitBidHistory,
+
+    /// Close a bid receipt after settlement, returning rent to the bidder
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Bid receipt PDA
+    CloseBidReceipt,
+
+    // ============ Maintenance ============
+    /// Rewrite an auction in the current layout version, growing the account
+    /// and topping up rent from the payer when the new layout is larger.
+    /// Auctions from the first release, stored without a discriminator, are
+    /// migrated too.
+    /// Accounts:
+    /// 0. `[signer, writable]` Payer
+    /// 1. `[writable]` Auction account
+    /// 2. `[]` System program
+    MigrateAuction,
+
+    /// Grow the program state to the current schema version, filling new
+    /// config fields with their defaults
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[writable]` Program state PDA
+    /// 2. `[]` System program
+    UpgradeProgramState,
+
+    /// Write the program version, fee rate and pause flags to return data
+    /// as a borsh-encoded `ProgramInfo`
+    /// Accounts:
+    /// 0. `[]` Program state PDA
+    GetVersion,
+
+    /// Create the fee vault for a payment mint, along with its token account
+    /// (the fee vault PDA's associated token account); required before the
+    /// first Dutch purchase or Penny bid in that mint
+    /// Accounts:
+    /// 0. `[signer, writable]` Payer
+    /// 1. `[writable]` Fee vault PDA for `payment_mint`
+    /// 2. `[writable]` Fee vault token account
+    /// 3. `[]` Payment mint
+    /// 4. `[]` Token program
+    /// 5. `[]` Associated token program
+    /// 6. `[]` System program
+    InitFeeVault { payment_mint: Pubkey },
+
+    /// Replace the auction's category and metadata URI (empty clears it).
+    /// Only allowed before the first bid.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    UpdateMetadata { category: u16, metadata_uri: String },
+
+    // ============ Incident Response ============
+    /// Move the full balance of one of an auction's vaults to a recovery
+    /// token account and freeze the auction. Owner only, and only while the
+    /// program is paused.
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Auction account
+    /// 3. `[writable]` Vault token account PDA selected by `vault`
+    /// 4. `[writable]` Recovery token account for the vault's mint
+    /// 5. `[]` Token program
+    /// 6. `[]` Dealer bond PDA for the bond vault, or item PDA for an item
+    ///    vault, holding the vault's bump
+    EmergencyWithdraw { vault: EmergencyVault },
+
+    /// Refund an Active auction that never received a bid once
+    /// `SWEEP_GRACE_PERIOD` has passed since its deadline (permissionless).
+    /// Items become returnable to the dealer through `CloseItemVault`, and
+    /// the emptied escrow is closed with its rent paid to the caller.
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Auction account
+    /// 3. `[writable]` Escrow token account PDA
+    /// 4. `[]` Token program
+    /// 5. `[writable]` Mint stats PDA (created if missing)
+    /// 6. `[writable]` Dealer stats PDA (created if missing)
+    /// 7. `[]` System program
+    SweepExpired,
+
+    /// Set how long winners have to claim items before the dealer may
+    /// reclaim them, in seconds
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetClaimWindow { claim_window: i64 },
+
+    // ============ Co-listing ============
+    /// Approve another wallet to deposit items into the auction. Proceeds
+    /// are then shared pro-rata by the valuations of each depositor's items,
+    /// with the dealer keeping the share of their own. The first approval
+    /// must come before any item is deposited, and all before the first bid.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Co-listing PDA (created if missing)
+    /// 3. `[]` System program
+    ApproveDepositor { depositor: Pubkey },
+
+    // ============ Delegation ============
+    /// Authorize `delegate` (e.g. a marketplace operator) to deposit items
+    /// and accept bids on the dealer's behalf; the default pubkey revokes.
+    /// Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetAuctionDelegate { delegate: Pubkey },
+
+    // ============ Session Keys ============
+    /// Open (or top up and re-key) a bid session letting `session_key` place
+    /// Penny bids on the auction for the bidder until `expires_at`, at most
+    /// `MAX_SESSION_DURATION` away. `credits` move from the bidder into the
+    /// session's token account and raise its spend cap.
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[]` Auction account (Penny)
+    /// 2. `[writable]` Bid session PDA (created if missing)
+    /// 3. `[writable]` Session credits token account (the session PDA's
+    ///    associated token account, created if missing)
+    /// 4. `[writable]` Bidder token account
+    /// 5. `[]` Payment mint
+    /// 6. `[]` Token program
+    /// 7. `[]` Associated token program
+    /// 8. `[]` System program
+    OpenBidSession {
+        session_key: Pubkey,
+        expires_at: i64,
+        credits: u64,
+    },
+
+    /// Place a Penny bid for the session's bidder, signed by its session key
+    /// and paid from its credits
+    /// Accounts: (same as BidPenny, except)
+    /// 0. `[signer, writable]` Session key (pays rent in the bidder's place)
+    /// 2. `[writable]` Session credits token account
+    /// 15. `[writable]` Bid session PDA
+    ///
+    /// The gate and optional accounts follow from 16, for the session's bidder.
+    BidPennySession { memo: Option<String> },
+
+    /// Close a bid session, returning unspent credits and rent to the bidder.
+    /// The bidder may close it at any time.
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Bid session PDA
+    /// 3. `[writable]` Session credits token account
+    /// 4. `[writable]` Bidder token account
+    /// 5. `[]` Token program
+    CloseBidSession,
+
+    // ============ USD Pricing ============
+    /// Set the reserve price, and optionally the minimum first bid, of a
+    /// Traditional auction in USD cents, converted to the payment mint with
+    /// the Pyth `price_feed` whenever a bid is placed or the auction is
+    /// finalized. A zero amount keeps the auction's own price. Dealer only,
+    /// before the first bid.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Traditional)
+    /// 2. `[writable]` USD pricing PDA (created if missing)
+    /// 3. `[]` Payment mint
+    /// 4. `[]` Pyth price account
+    /// 5. `[]` System program
+    SetUsdPricing {
+        price_feed: Pubkey,
+        reserve_usd_cents: u64,
+        start_usd_cents: u64,
+    },
+
+    /// Derive a Dutch auction's start price from the market: `lot_amount`
+    /// base units of the lot mint valued at the Pyth `price_feed`, times
+    /// `premium_bps` / 10_000, evaluated whenever a purchase is attempted.
+    /// The price then falls from there on the auction's schedule and floor.
+    /// Dealer only, while the auction is active.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Dutch)
+    /// 2. `[writable]` Dutch oracle PDA (created if missing)
+    /// 3. `[]` Payment mint
+    /// 4. `[]` Lot mint
+    /// 5. `[]` Pyth price account
+    /// 6. `[]` System program
+    SetDutchOracle {
+        price_feed: Pubkey,
+        premium_bps: u16,
+        lot_amount: u64,
+    },
+
+    // ============ Hidden Reserve ============
+    /// Reveal a Traditional auction's hidden reserve once bidding has ended,
+    /// typically in the same transaction as `FinalizeAuction`. Until then no
+    /// bid meets the reserve; one left unrevealed counts as met once the
+    /// acceptance period lapses. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    RevealReserve { reserve_price: u64, salt: [u8; 32] },
+
+    /// Change a Traditional auction's public reserve price before the first
+    /// bid, e.g. to correct a mistyped one. Hidden reserves cannot be
+    /// changed. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    UpdateReservePrice { reserve_price: u64 },
+
+    /// Change a Traditional auction's minimum raise before the first bid:
+    /// each bid must beat the current one by `increment` or `increment_bps`
+    /// basis points of it, whichever is larger. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetBidIncrement { increment: u64, increment_bps: u16 },
+
+    /// Set a Traditional auction's tiered increments before the first bid:
+    /// once the current bid reaches a tier's threshold, raises must be at
+    /// least its increment (or the `SetBidIncrement` minimum, if larger).
+    /// An empty table turns tiers off. Dealer only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Increment table PDA (created if missing)
+    /// 3. `[]` System program
+    SetIncrementTable { tiers: Vec<IncrementTier> },
+
+    /// Require a Traditional auction's bids to be multiples of `tick_size`
+    /// base units (0 allows any amount) before the first bid. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetTickSize { tick_size: u32 },
+
+    /// Withdraw a bidder's escrowed funds that no longer back the standing
+    /// bid: outbid amounts at any time, and the winning bid once the auction
+    /// is Refunded (Traditional only)
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Bidder token account
+    /// 4. `[writable]` Bid deposit PDA (closed once emptied)
+    /// 5. `[]` Token program
+    WithdrawLosingBid,
+
+    /// Name the arbiter of a Traditional auction's disputes before the first
+    /// bid; the default pubkey removes it. The winning payment of an
+    /// arbitrated sale stays in escrow through `DISPUTE_WINDOW` after
+    /// finalization, then is paid out with `ReleaseProceeds`. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetArbiter { arbiter: Pubkey },
+
+    /// Dispute an arbitrated sale within `DISPUTE_WINDOW` of finalization,
+    /// freezing item claims and the payment until the arbiter rules.
+    /// Dealer or winner only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer or winner
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Dispute PDA
+    /// 3. `[]` System program
+    OpenDispute,
+
+    /// Rule on an open dispute. Arbiter only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Arbiter
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Dispute PDA
+    /// 3. `[writable]` Escrow token account
+    /// 4. `[writable]` Dealer token account
+    /// 5. `[writable]` Fee vault token account
+    /// 6. `[writable]` Fee vault PDA
+    /// 7. `[]` Token program
+    ///
+    /// Payout split accounts are passed as for `BuyDutch`.
+    ResolveDispute { ruling: DisputeRuling },
+
+    /// Pay out held proceeds once `DISPUTE_WINDOW` (if arbitrated) and the
+    /// delivery timeout have passed without a dispute (permissionless)
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Dealer token account
+    /// 4. `[writable]` Fee vault token account
+    /// 5. `[writable]` Fee vault PDA
+    /// 6. `[]` Token program
+    ///
+    /// Payout split accounts are passed as for `BuyDutch`.
+    ReleaseProceeds,
+
+    /// Set how long after finalization the winning payment waits in escrow
+    /// for `ConfirmDelivery` before `ReleaseProceeds` may pay it out; 0 pays
+    /// the dealer at finalization. For goods delivered off-chain. Only before
+    /// the first bid. Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    SetDeliveryTimeout { timeout: u32 },
+
+    /// Confirm receipt of the goods, paying the held proceeds to the dealer.
+    /// Winner only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Winner
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Dealer token account
+    /// 4. `[writable]` Fee vault token account
+    /// 5. `[writable]` Fee vault PDA
+    /// 6. `[]` Token program
+    ///
+    /// Payout split accounts are passed as for `BuyDutch`.
+    ConfirmDelivery,
+
+    /// Vest a Traditional auction's proceeds linearly over `duration`
+    /// seconds from the sale's payout, instead of paying them at once; 0
+    /// turns vesting off. Only before the first bid. Dealer only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Proceeds vesting PDA (created if missing)
+    /// 3. `[]` System program
+    ///
+    /// Once set, the vesting PDA must be passed (writable) anywhere after
+    /// the accounts of the instruction paying the sale out: `FinalizeAuction`,
+    /// `AcceptBid`, `ResolveDispute`, `ReleaseProceeds` or `ConfirmDelivery`.
+    SetProceedsVesting { duration: u32 },
+
+    /// Pay out the vested and unclaimed proceeds (permissionless)
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Proceeds vesting PDA
+    /// 3. `[writable]` Escrow token account
+    /// 4. `[writable]` Dealer token account
+    /// 5. `[]` Token program
+    ///
+    /// Payout split accounts are passed as for `BuyDutch`.
+    ClaimVested,
+
+    /// Route `charity_bps` of a Traditional or Dutch auction's proceeds
+    /// (after the fee) to `charity` ahead of co-depositors, payout splits and
+    /// the dealer; the default pubkey removes it. Only before the first bid.
+    /// Dealer only.
+    /// Accounts:
+    /// 0. `[signer]` Dealer
+    /// 1. `[writable]` Auction account
+    ///
+    /// Once set, the charity's associated token account (writable) and the
+    /// charity must be passed anywhere after the accounts of every
+    /// instruction paying out proceeds; a missing token account is created
+    /// as for payout splits. If an item carries a royalty, all item PDAs follow
+    /// the accounts, and `charity_bps` plus any item's royalty may not
+    /// exceed 100%.
+    SetCharity { charity: Pubkey, charity_bps: u16 },
+
+    /// Burn the protocol fees collected in `payment_mint` instead of moving
+    /// them to its fee vault
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Fee burn PDA for `payment_mint`
+    /// 3. `[]` System program
+    ///
+    /// Instructions collecting a fee in the mint always pass its fee burn
+    /// PDA anywhere after their accounts, and while it is set the payment
+    /// mint writable too (see `with_fee_burn`); without an entry at the PDA
+    /// the fee goes to the fee vault.
+    EnableFeeBurn { payment_mint: Pubkey },
+
+    /// Collect `payment_mint`'s fees in its fee vault again, returning rent
+    /// to the owner
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Fee burn PDA for `payment_mint`
+    DisableFeeBurn { payment_mint: Pubkey },
+
+    /// Claim accumulated fees for several payment tokens at once; vaults
+    /// without fees are skipped
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[]` Token program
+    ///
+    /// Then, per payment token:
+    /// 0. `[writable]` Fee vault PDA
+    /// 1. `[writable]` Fee vault token account
+    /// 2. `[writable]` Owner token account
+    /// 3. `[]` Payment mint
+    ClaimFeesBatch,
+
+    /// Exempt a dealer's sales from the protocol fee
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Fee exemption PDA for `dealer`
+    /// 3. `[]` System program
+    ///
+    /// Auctions the dealer creates while exempt record it, and every
+    /// instruction charging a fee on them must pass the dealer's fee
+    /// exemption PDA anywhere after its accounts; once the exemption is
+    /// removed the fee is charged again.
+    AddFeeExemption { dealer: Pubkey },
+
+    /// Charge a dealer the protocol fee again, returning rent to the owner
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Fee exemption PDA for `dealer`
+    RemoveFeeExemption { dealer: Pubkey },
+
+    /// Swap a Traditional auction's proceeds into `target_mint`, which must
+    /// differ from the payment mint, at settlement, accepting at most
+    /// `max_slippage_bps` below the quoted output; the default pubkey turns
+    /// the swap off. The winning payment
+    /// then stays in escrow until settled with `SwapProceeds`. Only before
+    /// the first bid. Dealer only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Settlement swap PDA (created if missing)
+    /// 3. `[]` System program
+    SetSettlementSwap {
+        target_mint: Pubkey,
+        max_slippage_bps: u16,
+    },
+
+    /// Settle held proceeds by swapping the dealer's share through the
+    /// Jupiter `route` (instruction data built off-chain with the escrow as
+    /// the swapping wallet), which must return at least `quoted_out` less
+    /// the slippage bound. The fee, charity, co-depositors and payout splits
+    /// are paid in the payment mint first. Dealer only; the same windows as
+    /// `ReleaseProceeds` apply.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Dealer token account in the target mint
+    /// 4. `[writable]` Fee vault token account
+    /// 5. `[writable]` Fee vault PDA
+    /// 6. `[]` Token program
+    /// 7. `[]` Settlement swap PDA
+    /// 8. `[]` Jupiter program
+    ///
+    /// Then the `route_accounts` accounts of the route, followed by payout
+    /// split accounts as for `BuyDutch`.
+    SwapProceeds {
+        route: Vec<u8>,
+        route_accounts: u8,
+        quoted_out: u64,
+    },
+
+    /// Move the still-vaulted items of a Refunded auction into a new auction
+    /// of the same dealer, instead of reclaiming and depositing each one
+    /// again. The new auction is created as usual (typically earlier in the
+    /// same transaction) and must have no items or bids. Co-listed auctions
+    /// cannot be relisted. Dealer only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Refunded auction account
+    /// 2. `[writable]` New auction account
+    /// 3. `[]` Token program
+    /// 4. `[]` System program
+    ///
+    /// Then, per item of the refunded auction in index order:
+    /// 0. `[writable]` Item account
+    /// 1. `[writable]` Item vault token account
+    /// 2. `[writable]` Item account PDA in the new auction
+    /// 3. `[writable]` Item vault token account PDA in the new auction
+    /// 4. `[]` Item mint
+    ///
+    /// Rent of the old item accounts, and of vaults left empty, returns to
+    /// the dealer.
+    RelistAuction,
+
+    /// Chain auctions of the dealer into a series whose lots open for bids
+    /// one at a time, each once the lot before it has settled (see
+    /// `OpenNextLot`). The first lot stays open; the bidding windows of the
+    /// others start over when they open. Lots must be active, without bids
+    /// and in no other series. Dealer only.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Series PDA
+    /// 2. `[]` System program
+    ///
+    /// Then the auction account of each lot, in `lots` order (writable).
+    CreateSeries {
+        series_id: [u8; 32],
+        lots: Vec<[u8; 32]>,
+    },
+
+    /// Open the next lot of a series once the current lot is finalized or
+    /// refunded (permissionless)
+    /// Accounts:
+    /// 0. `[signer]` Caller
+    /// 1. `[writable]` Series PDA
+    /// 2. `[]` Current lot's auction account
+    /// 3. `[writable]` Next lot's auction account
+    OpenNextLot,
+
+    /// Set the reserve of a single item of the lot; if the winning bid falls
+    /// short of it the item is left out of the sale and returns to its
+    /// depositor. Items deposited before item reserves are grown to hold one,
+    /// the dealer paying the rent. Dealer only, before the first bid.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Auction item PDA
+    /// 3. `[]` System program
+    SetItemReserve { item_index: u8, reserve_price: u64 },
+
+    // ============ Clock Sales ============
+    /// Sell the single item of a Dutch auction to many buyers on a descending
+    /// clock: the auction's schedule prices one of `supply` equal units of
+    /// the item, buyers register intents as it falls, and all of them pay the
+    /// price at which demand covered the supply. A `supply` of 0 turns the
+    /// clock off. Dealer only, before the first intent.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Dutch)
+    /// 2. `[writable]` Auction item PDA (index 0, grown if deposited before
+    ///    item reserves)
+    /// 3. `[writable]` Clock sale PDA (created if missing)
+    /// 4. `[]` System program
+    SetClockSale { supply: u64 },
+
+    /// Register for `quantity` units of a clock sale at the current clock
+    /// price, escrowing the payment. Intents beyond the units left are
+    /// trimmed to them; the one covering the supply stops the clock.
+    /// `max_price` bounds the unit price against clock movement.
+    /// Accounts:
+    /// 0. `[signer, writable]` Buyer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Clock sale PDA
+    /// 3. `[writable]` Clock intent PDA
+    /// 4. `[writable]` Buyer token account
+    /// 5. `[writable]` Escrow token account
+    /// 6. `[]` Program state
+    /// 7. `[]` Token program
+    /// 8. `[]` System program
+    ///
+    /// Then the allowlist pass, gate holdings and bid history, as for
+    /// `BuyDutch`.
+    RegisterIntent { quantity: u64, max_price: u64 },
+
+    /// Clear a clock sale once demand covers the supply, or at the floor once
+    /// the auction's deadline has passed, paying the dealer for the units
+    /// sold. A sale nobody registered for is refunded (permissionless).
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Clock sale PDA
+    /// 3. `[writable]` Auction item PDA (index 0)
+    /// 4. `[writable]` Escrow token account
+    /// 5. `[writable]` Dealer token account (created if missing)
+    /// 6. `[writable]` Fee vault token account
+    /// 7. `[writable]` Fee vault PDA
+    /// 8. `[]` Program state
+    /// 9. `[]` Token program
+    /// 10. `[]` System program
+    /// 11. `[]` Dealer
+    /// 12. `[]` Payment mint
+    /// 13. `[]` Associated token program
+    ///
+    /// Payout splits, co-listing, vesting and charity accounts are passed as
+    /// for `FinalizeAuction`. Unsold units return to the dealer through
+    /// `CloseItemVault`.
+    ClearClockSale,
+
+    /// Deliver a buyer's units of a cleared clock sale and refund what they
+    /// escrowed above the clearing price (permissionless). The intent's rent
+    /// returns to the buyer.
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[]` Auction account
+    /// 2. `[]` Clock sale PDA
+    /// 3. `[writable]` Clock intent PDA
+    /// 4. `[writable]` Buyer
+    /// 5. `[writable]` Buyer item token account (created if missing)
+    /// 6. `[writable]` Buyer payment token account (created if missing)
+    /// 7. `[writable]` Item vault
+    /// 8. `[writable]` Escrow token account
+    /// 9. `[]` Item mint
+    /// 10. `[]` Payment mint
+    /// 11. `[]` Token program
+    /// 12. `[]` System program
+    /// 13. `[]` Associated token program
+    SettleIntent,
+
+    // ============ Curve Sales ============
+    /// Sell the single item of a Dutch auction in `supply` equal units priced
+    /// along a bonding curve of the units sold (see `BondingCurve`), until
+    /// the auction's deadline. With `sell_back`, buyers can return units
+    /// along the curve and payments stay in escrow until `CloseCurveSale`;
+    /// otherwise each buy pays the dealer at once. A `supply` of 0 turns the
+    /// curve off. Dealer only, before the first buy.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Dutch)
+    /// 2. `[writable]` Auction item PDA (index 0, grown if deposited before
+    ///    item reserves)
+    /// 3. `[writable]` Bonding curve PDA (created if missing)
+    /// 4. `[]` System program
+    SetBondingCurve {
+        supply: u64,
+        base_price: u64,
+        slope: u64,
+        exponent: u8,
+        sell_back: bool,
+    },
+
+    /// Buy `quantity` units along the curve for at most `max_cost`. Orders
+    /// beyond the units left are trimmed to them.
+    /// Accounts:
+    /// 0. `[signer, writable]` Buyer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Bonding curve PDA
+    /// 3. `[writable]` Buyer token account
+    /// 4. `[writable]` Buyer item token account (created if missing)
+    /// 5. `[writable]` Item vault
+    /// 6. `[writable]` Escrow token account
+    /// 7. `[writable]` Dealer token account (created if missing)
+    /// 8. `[writable]` Fee vault token account
+    /// 9. `[writable]` Fee vault PDA
+    /// 10. `[]` Program state
+    /// 11. `[]` Token program
+    /// 12. `[]` System program
+    /// 13. `[]` Dealer
+    /// 14. `[]` Payment mint
+    /// 15. `[]` Item mint
+    /// 16. `[]` Associated token program
+    ///
+    /// Then the allowlist pass, gate holdings and bid history, as for
+    /// `BuyDutch`. Payout splits, co-listing and charity accounts are passed
+    /// as for `BuyDutch` when the curve pays the dealer at once.
+    BuyCurve { quantity: u64, max_cost: u64 },
+
+    /// Sell `quantity` units back along the curve before the deadline,
+    /// receiving at least `min_refund` after the fee
+    /// Accounts:
+    /// 0. `[signer]` Seller
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Bonding curve PDA
+    /// 3. `[writable]` Seller item token account
+    /// 4. `[writable]` Item vault
+    /// 5. `[writable]` Escrow token account
+    /// 6. `[writable]` Seller token account
+    /// 7. `[writable]` Fee vault token account
+    /// 8. `[writable]` Fee vault PDA
+    /// 9. `[]` Program state
+    /// 10. `[]` Token program
+    SellCurve { quantity: u64, min_refund: u64 },
+
+    /// Close a curve sale at the auction's deadline, or once sold out if it
+    /// does not buy back, paying the dealer the escrowed reserve
+    /// (permissionless). A sale without buyers is refunded.
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Bonding curve PDA
+    /// 3. `[writable]` Auction item PDA (index 0)
+    /// 4. `[writable]` Escrow token account
+    /// 5. `[writable]` Dealer token account (created if missing)
+    /// 6. `[writable]` Fee vault token account
+    /// 7. `[writable]` Fee vault PDA
+    /// 8. `[]` Program state
+    /// 9. `[]` Token program
+    /// 10. `[]` System program
+    /// 11. `[]` Dealer
+    /// 12. `[]` Payment mint
+    /// 13. `[]` Associated token program
+    ///
+    /// Payout splits, co-listing, vesting and charity accounts are passed as
+    /// for `FinalizeAuction`. Unsold units return to the dealer through
+    /// `CloseItemVault`.
+    CloseCurveSale,
+
+    // ============ Harberger Listings ============
+    /// List the single item of a Dutch auction under a `HarbergerListing`
+    /// until the auction's deadline: the dealer holds it at `price` until
+    /// someone buys it, and each later holder pays `tax_bps` of their
+    /// assessed price per `tax_period` seconds. A `price` of 0 turns the
+    /// listing off. Dealer only, before the first buy.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Dutch)
+    /// 2. `[]` Auction item PDA (index 0)
+    /// 3. `[writable]` Harberger listing PDA (created if missing)
+    /// 4. `[]` System program
+    SetHarbergerListing {
+        price: u64,
+        tax_bps: u16,
+        tax_period: i64,
+    },
+
+    /// Reassess the held item at `price`, adding `deposit` to the prepaid
+    /// tax. Tax owed at the old price is accrued first. Holder only.
+    /// Accounts:
+    /// 0. `[signer]` Holder
+    /// 1. `[]` Auction account
+    /// 2. `[writable]` Harberger listing PDA
+    /// 3. `[writable]` Holder token account
+    /// 4. `[writable]` Escrow token account
+    /// 5. `[]` Program state
+    /// 6. `[]` Token program
+    SetAssessedPrice { price: u64, deposit: u64 },
+
+    /// Take the item from its holder at their assessed price (at most
+    /// `max_price`), assessing it at `assessed_price` and depositing
+    /// `deposit` toward the tax. The previous holder is paid the price and
+    /// refunded what is left of their deposit; a sale by the dealer pays the
+    /// fee and proceeds as for `BuyDutch`.
+    /// Accounts:
+    /// 0. `[signer, writable]` Buyer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Harberger listing PDA
+    /// 3. `[writable]` Buyer token account
+    /// 4. `[writable]` Holder token account (created if missing)
+    /// 5. `[writable]` Escrow token account
+    /// 6. `[writable]` Fee vault token account
+    /// 7. `[writable]` Fee vault PDA
+    /// 8. `[]` Program state
+    /// 9. `[]` Token program
+    /// 10. `[]` System program
+    /// 11. `[]` Holder
+    /// 12. `[]` Payment mint
+    /// 13. `[]` Associated token program
+    ///
+    /// Then the allowlist pass, gate holdings and bid history, as for
+    /// `BuyDutch`. Payout splits, co-listing and charity accounts are passed
+    /// as for `BuyDutch` in case the dealer holds the item.
+    ForceBuy {
+        max_price: u64,
+        assessed_price: u64,
+        deposit: u64,
+    },
+
+    /// Close a Harberger listing at the auction's deadline (permissionless),
+    /// paying the dealer the tax collected and refunding the holder's
+    /// deposit. The holder claims the item through `CloseItemVault`; a
+    /// listing the dealer still holds is refunded.
+    /// Accounts:
+    /// 0. `[signer, writable]` Caller
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Harberger listing PDA
+    /// 3. `[writable]` Escrow token account
+    /// 4. `[writable]` Holder token account (created if missing)
+    /// 5. `[writable]` Dealer token account (created if missing)
+    /// 6. `[writable]` Fee vault token account
+    /// 7. `[writable]` Fee vault PDA
+    /// 8. `[]` Program state
+    /// 9. `[]` Token program
+    /// 10. `[]` System program
+    /// 11. `[]` Holder
+    /// 12. `[]` Dealer
+    /// 13. `[]` Payment mint
+    /// 14. `[]` Associated token program
+    ///
+    /// Payout splits, co-listing and charity accounts are passed as for
+    /// `FinalizeAuction`.
+    CloseHarbergerListing,
+
+    // ============ Collection Bids ============
+    /// Place or replace a standing bid of `price` for each of `quantity` NFTs
+    /// of a verified Metaplex `collection`, lapsing at `expires_at` (0 for
+    /// never). The bid's token account is topped up to, or refunded down to,
+    /// `price * quantity`.
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[writable]` Collection bid PDA (created if missing)
+    /// 2. `[writable]` Collection bid token account (created if missing)
+    /// 3. `[writable]` Bidder token account
+    /// 4. `[]` Payment mint
+    /// 5. `[]` Program state
+    /// 6. `[]` Token program
+    /// 7. `[]` Associated token program
+    /// 8. `[]` System program
+    PlaceCollectionBid {
+        collection: Pubkey,
+        price: u64,
+        quantity: u64,
+        expires_at: i64,
+    },
+
+    /// Withdraw a standing collection bid, refunding its escrow and closing
+    /// its accounts
+    /// Accounts:
+    /// 0. `[signer, writable]` Bidder
+    /// 1. `[writable]` Collection bid PDA
+    /// 2. `[writable]` Collection bid token account
+    /// 3. `[writable]` Bidder token account
+    /// 4. `[]` Token program
+    CancelCollectionBid { collection: Pubkey },
+
+    /// Sell the auction's single NFT to a standing collection bid paying at
+    /// least `min_price`, settling the auction at once. Only before the
+    /// first bid.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer or their delegate
+    /// 1. `[writable]` Auction account
+    /// 2. `[]` Auction item PDA (index 0)
+    /// 3. `[]` Item metadata account
+    /// 4. `[writable]` Collection bid PDA
+    /// 5. `[writable]` Collection bid token account
+    /// 6. `[writable]` Escrow token account
+    /// 7. `[writable]` Dealer token account
+    /// 8. `[writable]` Fee vault token account
+    /// 9. `[writable]` Fee vault PDA
+    /// 10. `[]` Program state
+    /// 11. `[]` Token program
+    /// 12. `[writable]` Mint stats PDA (created if missing)
+    /// 13. `[]` System program
+    /// 14. `[writable]` Dealer stats PDA (created if missing)
+    ///
+    /// The bidder's stats PDA is optional, as for `BidTraditional`. Payout
+    /// split accounts are passed as for `AcceptBid`.
+    AcceptCollectionBid { min_price: u64 },
+
+    /// Place a Traditional bid `bidder` signed off-chain for `amount`, valid
+    /// until `expires_at`, paid from their token account through the bid
+    /// delegate PDA they approved on it. `nonce` must be the delegate's
+    /// current nonce, which the bid advances. The transaction must verify
+    /// the signature with an Ed25519 program instruction before this one
+    /// (see `SignedBidOrder`).
+    /// Accounts: (same as BidTraditional, except)
+    /// 0. `[signer, writable]` Dealer or matcher submitting the order (pays
+    ///    rent in the bidder's place)
+    /// 12. `[]` Instructions sysvar
+    /// 13. `[writable]` Bidder's bid delegate PDA (created if missing)
+    ///
+    /// The gate and optional accounts follow from 14, for the bidder. There
+    /// is no memo.
+    SettleSignedBid {
+        bidder: Pubkey,
+        amount: u64,
+        expires_at: i64,
+        nonce: u64,
+    },
+
+    /// Set the dealer's acceptance window for auctions created from now on,
+    /// in seconds, between `MIN_ACCEPTANCE_PERIOD` and `MAX_ACCEPTANCE_PERIOD`
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetAcceptancePeriod { acceptance_period: i64 },
+
+    /// Dealer declines the winning bid below reserve during the acceptance
+    /// period (Traditional only), refunding the bidder and marking the
+    /// auction Refunded. Like `AcceptBid`, an Active auction past its
+    /// deadline needs no `FinalizeAuction` first.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer or their delegate
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Winning bidder token account (created if missing)
+    /// 4. `[]` Program state
+    /// 5. `[]` Token program
+    /// 6. `[writable]` Mint stats PDA (created if missing)
+    /// 7. `[]` System program
+    /// 8. `[writable]` Dealer stats PDA (created if missing)
+    /// 9. `[]` Winning bidder
+    /// 10. `[]` Payment mint
+    /// 11. `[]` Associated token program
+    ///
+    /// The winner's deposit PDA must be passed anywhere after the accounts
+    /// above; a bid it covers stays in escrow for `WithdrawLosingBid`.
+    RejectBid,
+
+    /// Winning bidder reclaims their bid below reserve once the acceptance
+    /// period has lapsed (Traditional only), marking the auction Refunded
+    /// without waiting for a `FinalizeAuction`. All of their deposit is paid
+    /// back and the deposit closed.
+    /// Accounts:
+    /// 0. `[signer, writable]` Winning bidder
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Escrow token account
+    /// 3. `[writable]` Bidder token account
+    /// 4. `[writable]` Bid deposit PDA
+    /// 5. `[]` Token program
+    /// 6. `[writable]` Mint stats PDA (created if missing)
+    /// 7. `[writable]` Dealer stats PDA (created if missing)
+    /// 8. `[]` System program
+    ///
+    /// USD pricing accounts are passed as for `FinalizeAuction`; a USD reserve
+    /// met at the current price must be finalized instead.
+    ClaimExpiredBidRefund,
+
+    // ============ Unit Sales ============
+    /// Sell the single item of a Dutch auction in `supply` equal units, the
+    /// auction's schedule pricing one unit, so buyers take any quantity at
+    /// the current unit price until the deadline. A `supply` of 0 turns the
+    /// unit sale off. Dealer only, before the first buy.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account (Dutch)
+    /// 2. `[writable]` Auction item PDA (index 0, grown if deposited before
+    ///    item reserves)
+    /// 3. `[writable]` Unit sale PDA (created if missing)
+    /// 4. `[]` System program
+    SetUnitSale { supply: u64 },
+
+    /// Buy `quantity` units of a unit sale, paying the current unit price
+    /// for each; `max_price` bounds the unit price. Orders beyond the units
+    /// left are trimmed to them.
+    /// Accounts:
+    /// 0. `[signer, writable]` Buyer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Unit sale PDA
+    /// 3. `[writable]` Buyer token account
+    /// 4. `[writable]` Buyer item token account (created if missing)
+    /// 5. `[writable]` Item vault
+    /// 6. `[writable]` Dealer token account (created if missing)
+    /// 7. `[writable]` Fee vault token account
+    /// 8. `[writable]` Fee vault PDA
+    /// 9. `[]` Program state
+    /// 10. `[]` Token program
+    /// 11. `[]` System program
+    /// 12. `[]` Dealer
+    /// 13. `[]` Payment mint
+    /// 14. `[]` Item mint
+    /// 15. `[]` Associated token program
+    ///
+    /// Then the allowlist pass, gate holdings and bid history, as for
+    /// `BuyDutch`. Payout splits, co-listing and charity accounts are passed
+    /// as for `BuyDutch`.
+    BuyUnits { quantity: u64, max_price: u64 },
+
+    /// Close a unit sale at the auction's deadline, or once sold out
+    /// (permissionless). A sale without buyers is refunded; unsold units
+    /// return to the dealer through `CloseItemVault`.
+    /// Accounts:
+    /// 0. `[signer]` Caller
+    /// 1. `[writable]` Auction account
+    /// 2. `[]` Unit sale PDA
+    /// 3. `[writable]` Auction item PDA (index 0)
+    /// 4. `[]` Program state
+    CloseUnitSale,
+
+    // ============ Fee Overrides ============
+    /// Charge one auction `fee_bps` in place of the standard fee and any
+    /// dealer exemption, e.g. a negotiated 0% for a flagship drop. Owner
+    /// only, while the auction is active; setting it again updates the rate.
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Auction account
+    /// 3. `[writable]` Fee override PDA (created if missing)
+    /// 4. `[]` System program
+    ///
+    /// Every instruction charging a fee on the auction then passes the fee
+    /// override PDA anywhere after its accounts.
+    SetFeeOverride { fee_bps: u16 },
+
+    /// Charge an auction the standard fee again, returning rent to the owner
+    /// Accounts:
+    /// 0. `[signer, writable]` Owner
+    /// 1. `[]` Program state PDA
+    /// 2. `[writable]` Auction account
+    /// 3. `[writable]` Fee override PDA
+    RemoveFeeOverride,
+
+    // ============ Semi-fungible Items ============
+    /// Deposit `quantity` units of a semi-fungible token (0 decimals, supply
+    /// above 1) as one item, flagged so UIs show it as a stack of editions
+    /// rather than a fungible amount or a 1/1 NFT
+    /// Accounts: (same as DepositTokens)
+    ///
+    /// Then the mint's Metaplex metadata account, which must describe a
+    /// fungible asset.
+    DepositSft { quantity: u64, valuation: u64 },
+
+    // ============ Item Royalties ============
+    /// Pay `beneficiary` `royalty_bps` of an item's share of the proceeds
+    /// left after the charity's, replacing the royalty it took from its
+    /// Metaplex metadata (a `royalty_bps` of 0 removes it). Items deposited
+    /// before royalties are grown to hold one, the dealer paying the rent.
+    /// The royalty plus the auction's charity share may not exceed 100%.
+    /// Dealer only, before the first bid, on auctions paying proceeds out at
+    /// once.
+    /// Accounts:
+    /// 0. `[signer, writable]` Dealer
+    /// 1. `[writable]` Auction account
+    /// 2. `[writable]` Auction item PDA
+    /// 3. `[]` System program
+    ///
+    /// Once an item carries a royalty, every instruction paying out the
+    /// auction's proceeds passes all its item PDAs, the beneficiaries'
+    /// associated token accounts and the beneficiaries anywhere after its
+    /// accounts; a missing token account is created as for payout splits.
+    SetItemRoyalty {
+        item_index: u8,
+        royalty_bps: u16,
+        beneficiary: Pubkey,
+    },
+
+    // ============ Deployment ============
+    /// Initialize the program state with its config and create the fee
+    /// vaults of the initial payment mints in one transaction. The fee rate
+    /// is fixed at `FEE_RATE` and fees collect in the fee vaults, so the
+    /// config is the curated mode, claim window and acceptance period.
+    /// Accounts:
+    /// 0. `[signer, writable]` Payer, who becomes the owner
+    /// 1. `[writable]` Program state PDA
+    /// 2. `[]` Token program
+    /// 3. `[]` Associated token program
+    /// 4. `[]` System program
+    ///
+    /// Then, per payment mint:
+    /// 0. `[writable]` Fee vault PDA
+    /// 1. `[writable]` Fee vault token account
+    /// 2. `[]` Payment mint
+    Bootstrap {
+        curated: bool,
+        claim_window: i64,
+        acceptance_period: i64,
+    },
+
+    // ============ Creation Pause ============
+    /// Pause/unpause creating auctions, leaving live auctions to run to
+    /// completion
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetCreationPaused { paused: bool },
+
+    // ============ Bidding Pause ============
+    /// Pause/unpause bids and purchases, leaving finalization, refunds and
+    /// item claims working so funds are never trapped
+    /// Accounts:
+    /// 0. `[signer]` Owner
+    /// 1. `[writable]` Program state PDA
+    SetBiddingPaused { paused: bool },
+
+    // ============ Views ============
+    /// Write an auction's status, current Dutch price, minimum next bid and
+    /// deadlines to return data as a borsh-encoded `AuctionInfo`, priced the
+    /// way a bid placed now would be
+    /// Accounts:
+    /// 0. `[]` Auction account
+    ///
+    /// Then the accounts a bid or purchase passes to price the auction: its
+    /// increment table, or its USD pricing or Dutch oracle PDA and price feed.
+    GetAuctionInfo,
+}
+
+/// Serialize `instruction` with the program's borsh version
+fn new_instruction(
+    program_id: Pubkey,
+    instruction: &AuctionInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts,
+        data: borsh::to_vec(instruction).expect("instruction serializes into a Vec"),
+    }
+}
+
+/// Auction-owned token vault drained by `EmergencyWithdraw`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmergencyVault {
+    /// Payment escrow holding the current bid
+    Escrow,
+    /// Vault holding the dealer's bond
+    BondVault,
+    /// Vault holding the deposited item at `index` of `mint`
+    ItemVault { mint: Pubkey, index: u8 },
+}
+
+/// Arbiter's ruling on a `Dispute`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeRuling {
+    /// Uphold the sale: the dealer is paid
+    Release,
+    /// Undo the sale: the winner withdraws their bid with
+    /// `WithdrawLosingBid` and the items go back to their depositors
+    Refund,
+    /// Refund `refund_bps` of the payment to the winner, to withdraw with
+    /// `WithdrawLosingBid`, and pay the dealer the rest
+    Split { refund_bps: u16 },
+}
+
+/// Bidder-owned accounts proving an auction's holding gates
+///
+/// Only the fields for gates the auction actually has are used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GateAccounts {
+    /// Token account holding the auction's `gate_mint`
+    pub gate_token: Pubkey,
+    /// Token account holding an NFT from the gate collection
+    pub nft_token: Pubkey,
+    /// Mint of that NFT, used to derive its metadata account
+    pub nft_mint: Pubkey,
+}
+
+/// Trailing accounts every bid on `auction` must pass, in processor order
+fn bid_gate_accounts(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    gates: &GateAccounts,
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::new();
+    if auction.has_allowlist() {
+        let (pass, _) = find_allowlist_pass_address(program_id, &auction.auction_id, bidder);
+        accounts.push(AccountMeta::new_readonly(pass, false));
+    }
+    if auction.has_token_gate() {
+        accounts.push(AccountMeta::new_readonly(gates.gate_token, false));
+    }
+    if auction.has_collection_gate() {
+        let (metadata, _) = find_metadata_address(&gates.nft_mint);
+        accounts.push(AccountMeta::new_readonly(gates.nft_token, false));
+        accounts.push(AccountMeta::new_readonly(metadata, false));
+    }
+    if auction.has_bid_history {
+        let (history, _) = find_bid_history_address(program_id, &auction.auction_id);
+        accounts.push(AccountMeta::new(history, false));
+    }
+    accounts
+}
+
+/// Append the SPL Noop program when events are CPI'd through it
+fn with_event_accounts(accounts: Vec<AccountMeta>) -> Vec<AccountMeta> {
+    #[cfg(feature = "noop-events")]
+    {
+        let mut accounts = accounts;
+        accounts.push(AccountMeta::new_readonly(
+            crate::events::NOOP_PROGRAM_ID,
+            false,
+        ));
+        accounts
+    }
+    #[cfg(not(feature = "noop-events"))]
+    accounts
+}
+
+/// Append the SPL Memo program when a memo is attached
+fn with_memo_account(mut accounts: Vec<AccountMeta>, memo: Option<&str>) -> Vec<AccountMeta> {
+    if memo.is_some() {
+        accounts.push(AccountMeta::new_readonly(MEMO_PROGRAM_ID, false));
+    }
+    accounts
+}
+
+/// Append the accounts paying `payout_splits` of `auction` to a `BuyDutch`,
+/// `FinalizeAuction` or `AcceptBid` instruction
+pub fn with_payout_splits(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    payout_splits: &PayoutSplits,
+) -> Instruction {
+    let (splits, _) = find_payout_splits_address(program_id, &auction.auction_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(splits, false));
+    for split in payout_splits.splits() {
+        with_payout_recipient(&mut instruction.accounts, auction, &split.recipient);
+    }
+    instruction
+}
+
+/// Append the accounts paying the co-depositors of `auction` to a `BuyDutch`,
+/// `FinalizeAuction` or `AcceptBid` instruction
+pub fn with_co_listing(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    co_listing: &CoListing,
+) -> Instruction {
+    let (co_listing_address, _) = find_co_listing_address(program_id, &auction.auction_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(co_listing_address, false));
+    for co_depositor in co_listing.depositors() {
+        with_payout_recipient(&mut instruction.accounts, auction, &co_depositor.depositor);
+    }
+    instruction
+}
+
+/// Append the item PDAs of `auction` and the associated token accounts of
+/// its royalty beneficiaries, with the beneficiaries, to an instruction
+/// paying out its proceeds
+pub fn with_item_royalties(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    items: &[AuctionItem],
+) -> Instruction {
+    for item in items {
+        let (item_address, _) = find_item_address(program_id, &auction.auction_id, item.index);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(item_address, false));
+        if item.has_royalty() {
+            with_payout_recipient(
+                &mut instruction.accounts,
+                auction,
+                &item.royalty_beneficiary,
+            );
+        }
+    }
+    instruction
+}
+
+/// Append the accounts delivering `items` of `auction` to `buyer` to a
+/// `BuyDutch` instruction
+pub fn with_item_delivery(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    items: &[AuctionItem],
+) -> Instruction {
+    for item in items {
+        let (item_address, _) = find_item_address(program_id, &auction.auction_id, item.index);
+        let (item_vault, _) = find_item_vault_address(program_id, auction, item.index, &item.mint);
+        let buyer_token =
+            spl_associated_token_account::get_associated_token_address(buyer, &item.mint);
+        instruction.accounts.extend([
+            AccountMeta::new(item_address, false),
+            AccountMeta::new(item_vault, false),
+            AccountMeta::new(buyer_token, false),
+            AccountMeta::new_readonly(item.mint, false),
+        ]);
+    }
+    instruction
+}
+
+/// Append the accounts converting the USD prices of `auction` to a
+/// `BidTraditional`, `FinalizeAuction` or `GetAuctionInfo` instruction
+pub fn with_usd_pricing(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    usd_pricing: &UsdPricing,
+) -> Instruction {
+    let (usd_pricing_address, _) = find_usd_pricing_address(program_id, &auction.auction_id);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(usd_pricing_address, false),
+        AccountMeta::new_readonly(usd_pricing.price_feed, false),
+    ]);
+    instruction
+}
+
+/// Append the accounts pricing a Dutch `auction` from the market to a
+/// `BuyDutch` or `GetAuctionInfo` instruction
+pub fn with_dutch_oracle(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+    dutch_oracle: &DutchOracle,
+) -> Instruction {
+    let (dutch_oracle_address, _) = find_dutch_oracle_address(program_id, &auction.auction_id);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(dutch_oracle_address, false),
+        AccountMeta::new_readonly(dutch_oracle.price_feed, false),
+    ]);
+    instruction
+}
+
+/// Append the increment table of `auction` to a `BidTraditional` or
+/// `GetAuctionInfo` instruction
+pub fn with_increment_table(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    auction: &Auction,
+) -> Instruction {
+    let (increment_table, _) = find_increment_table_address(program_id, &auction.auction_id);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(increment_table, false));
+    instruction
+}
+
+/// Opt `bidder` into stats tracking on a bid, `BuyDutch`, `FinalizeAuction`,
+/// `AcceptBid` or `AcceptCollectionBid` instruction. For the last three,
+/// `bidder` is the winner.
+pub fn with_bidder_stats(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+) -> Instruction {
+    let (stats, _) = find_bidder_stats_address(program_id, bidder);
+    instruction.accounts.push(AccountMeta::new(stats, false));
+    instruction
+}
+
+/// Build an `Initialize` instruction
+pub fn initialize(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::Initialize,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(state, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `Bootstrap` instruction creating the fee vaults of
+/// `payment_mints`
+pub fn bootstrap(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    curated: bool,
+    claim_window: i64,
+    acceptance_period: i64,
+    payment_mints: &[Pubkey],
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for payment_mint in payment_mints {
+        let (fee_vault, _) = find_fee_vault_address(program_id, payment_mint);
+        let fee_vault_token = find_fee_vault_token_address(program_id, payment_mint);
+        accounts.extend([
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(fee_vault_token, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+        ]);
+    }
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::Bootstrap {
+            curated,
+            claim_window,
+            acceptance_period,
+        },
+        accounts,
+    )
+}
+
+/// Owner-signed instruction touching only the program state
+fn owner_state_instruction(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    instruction: &AuctionInstruction,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        instruction,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(state, false),
+        ],
+    )
+}
+
+/// Build a `SetPaused` instruction
+pub fn set_paused(program_id: &Pubkey, owner: &Pubkey, paused: bool) -> Instruction {
+    owner_state_instruction(program_id, owner, &AuctionInstruction::SetPaused { paused })
+}
+
+/// Build a `TransferOwnership` instruction
+pub fn transfer_ownership(program_id: &Pubkey, owner: &Pubkey, new_owner: &Pubkey) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::TransferOwnership {
+            new_owner: *new_owner,
+        },
+    )
+}
+
+/// Build a `ClaimFees` instruction claiming `amount` of the fees, or all of
+/// them if `None`
+pub fn claim_fees(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    payment_mint: &Pubkey,
+    owner_token: &Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ClaimFees { amount },
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(fee_vault_token, false),
+            AccountMeta::new(*owner_token, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build a `ClaimFeesBatch` instruction claiming the fees of each payment
+/// mint into the paired owner token account
+pub fn claim_fees_batch(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    claims: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for (payment_mint, owner_token) in claims {
+        let (fee_vault, _) = find_fee_vault_address(program_id, payment_mint);
+        let fee_vault_token = find_fee_vault_token_address(program_id, payment_mint);
+        accounts.extend([
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(fee_vault_token, false),
+            AccountMeta::new(*owner_token, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+        ]);
+    }
+    new_instruction(*program_id, &AuctionInstruction::ClaimFeesBatch, accounts)
+}
+
+/// Build an `EmergencyWithdraw` instruction draining `vault` of `auction`
+/// into `recovery_token`
+pub fn emergency_withdraw(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    auction: &Auction,
+    vault: EmergencyVault,
+    recovery_token: &Pubkey,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (vault_address, _) = match vault {
+        EmergencyVault::Escrow => find_escrow_address(program_id, &auction.auction_id),
+        EmergencyVault::BondVault => find_bond_vault_address(program_id, &auction.auction_id),
+        EmergencyVault::ItemVault { mint, index } => {
+            find_item_vault_address(program_id, auction, index, &mint)
+        }
+    };
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(vault_address, false),
+        AccountMeta::new(*recovery_token, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    match vault {
+        EmergencyVault::Escrow => {}
+        EmergencyVault::BondVault => {
+            let (bond, _) = find_bond_address(program_id, &auction.auction_id);
+            accounts.push(AccountMeta::new_readonly(bond, false));
+        }
+        EmergencyVault::ItemVault { index, .. } => {
+            let (item, _) = find_item_address(program_id, &auction.auction_id, index);
+            accounts.push(AccountMeta::new_readonly(item, false));
+        }
+    }
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::EmergencyWithdraw { vault },
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `SweepExpired` instruction paying the escrow rent to `caller`
+pub fn sweep_expired(program_id: &Pubkey, caller: &Pubkey, auction: &Auction) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SweepExpired,
+        with_event_accounts(vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(auction_address, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(mint_stats, false),
+            AccountMeta::new(dealer_stats, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]),
+    )
+}
+
+/// Counters selecting the index pages a new auction is appended to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CreateIndexes {
+    /// The dealer's `DealerIndex::auction_count` (0 before their first auction)
+    pub dealer_auction_count: u64,
+    /// `ProgramState::auction_count`, which becomes the auction's registry index
+    pub auction_count: u64,
+}
+
+/// Accounts shared by the three create instructions
+fn create_auction_accounts(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    payment_mint: &Pubkey,
+    auction_id: &[u8; 32],
+    indexes: &CreateIndexes,
+    payout_splits: &[PayoutSplit],
+) -> Vec<AccountMeta> {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (escrow, _) = find_escrow_address(program_id, auction_id);
+    let (state, _) = find_program_state_address(program_id);
+    let (blacklist, _) = find_blacklist_address(program_id, dealer);
+    let (profile, _) = find_dealer_profile_address(program_id, dealer);
+    let (bond_config, _) = find_bond_config_address(program_id, payment_mint);
+    let (dealer_index, _) = find_dealer_index_address(program_id, dealer);
+    let (dealer_index_page, _) = find_dealer_index_page_address(
+        program_id,
+        dealer,
+        DealerIndex::page_of(indexes.dealer_auction_count),
+    );
+    let (registry_page, _) =
+        find_registry_page_address(program_id, RegistryPage::locate(indexes.auction_count).0);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, dealer);
+    let mut accounts = vec![
+        AccountMeta::new(*dealer, true),
+        AccountMeta::new(auction, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(state, false),
+        AccountMeta::new_readonly(*payment_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(blacklist, false),
+        AccountMeta::new_readonly(profile, false),
+        AccountMeta::new_readonly(bond_config, false),
+        AccountMeta::new(dealer_index, false),
+        AccountMeta::new(dealer_index_page, false),
+        AccountMeta::new(registry_page, false),
+        AccountMeta::new(dealer_stats, false),
+    ];
+    if !payout_splits.is_empty() {
+        let (splits, _) = find_payout_splits_address(program_id, auction_id);
+        accounts.push(AccountMeta::new(splits, false));
+    }
+    let (fee_exemption, _) = find_fee_exemption_address(program_id, dealer);
+    accounts.push(AccountMeta::new_readonly(fee_exemption, false));
+    with_event_accounts(accounts)
+}
+
+/// Build a `CreateTraditionalAuction` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn create_traditional_auction(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    payment_mint: &Pubkey,
+    indexes: &CreateIndexes,
+    auction_id: [u8; 32],
+    start_amount: u64,
+    increment: u64,
+    reserve_price: u64,
+    deadline: i64,
+    category: u16,
+    metadata_uri: &str,
+    payout_splits: &[PayoutSplit],
+    reserve_commitment: [u8; 32],
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CreateTraditionalAuction {
+            auction_id,
+            start_amount,
+            increment,
+            reserve_price,
+            deadline,
+            category,
+            metadata_uri: metadata_uri.to_string(),
+            payout_splits: payout_splits.to_vec(),
+            reserve_commitment,
+        },
+        create_auction_accounts(
+            program_id,
+            dealer,
+            payment_mint,
+            &auction_id,
+            indexes,
+            payout_splits,
+        ),
+    )
+}
+
+/// Build a `CreateDutchAuction` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn create_dutch_auction(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    payment_mint: &Pubkey,
+    indexes: &CreateIndexes,
+    auction_id: [u8; 32],
+    start_price: u64,
+    decrease_amount: u64,
+    interval: i64,
+    minimum_price: u64,
+    deadline: i64,
+    category: u16,
+    metadata_uri: &str,
+    payout_splits: &[PayoutSplit],
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CreateDutchAuction {
+            auction_id,
+            start_price,
+            decrease_amount,
+            interval,
+            minimum_price,
+            deadline,
+            category,
+            metadata_uri: metadata_uri.to_string(),
+            payout_splits: payout_splits.to_vec(),
+        },
+        create_auction_accounts(
+            program_id,
+            dealer,
+            payment_mint,
+            &auction_id,
+            indexes,
+            payout_splits,
+        ),
+    )
+}
+
+/// Build a `CreatePennyAuction` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn create_penny_auction(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    payment_mint: &Pubkey,
+    indexes: &CreateIndexes,
+    auction_id: [u8; 32],
+    increment: u64,
+    timer_duration: i64,
+    category: u16,
+    metadata_uri: &str,
+    payout_splits: &[PayoutSplit],
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CreatePennyAuction {
+            auction_id,
+            increment,
+            timer_duration,
+            category,
+            metadata_uri: metadata_uri.to_string(),
+            payout_splits: payout_splits.to_vec(),
+        },
+        create_auction_accounts(
+            program_id,
+            dealer,
+            payment_mint,
+            &auction_id,
+            indexes,
+            payout_splits,
+        ),
+    )
+}
+
+/// Build a `DepositTokens` instruction for the auction's next item slot
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_tokens(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    auction: &Auction,
+    depositor_token: &Pubkey,
+    token_mint: &Pubkey,
+    amount: u64,
+    valuation: u64,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::DepositTokens { amount, valuation },
+        deposit_accounts(program_id, depositor, auction, depositor_token, token_mint),
+    )
+}
+
+/// Build a `DepositNft` instruction for the auction's next item slot
+pub fn deposit_nft(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    auction: &Auction,
+    depositor_token: &Pubkey,
+    nft_mint: &Pubkey,
+    valuation: u64,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::DepositNft { valuation },
+        with_metadata(
+            deposit_accounts(program_id, depositor, auction, depositor_token, nft_mint),
+            nft_mint,
+        ),
+    )
+}
+
+/// Build a `DepositSft` instruction for the auction's next item slot
+pub fn deposit_sft(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    auction: &Auction,
+    depositor_token: &Pubkey,
+    sft_mint: &Pubkey,
+    quantity: u64,
+    valuation: u64,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::DepositSft {
+            quantity,
+            valuation,
+        },
+        with_metadata(
+            deposit_accounts(program_id, depositor, auction, depositor_token, sft_mint),
+            sft_mint,
+        ),
+    )
+}
+
+/// Append the Metaplex metadata account of `mint` to deposit `accounts`
+fn with_metadata(mut accounts: Vec<AccountMeta>, mint: &Pubkey) -> Vec<AccountMeta> {
+    let (metadata, _) = find_metadata_address(mint);
+    accounts.push(AccountMeta::new_readonly(metadata, false));
+    accounts
+}
+
+fn deposit_accounts(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    auction: &Auction,
+    depositor_token: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (item, _) = find_item_address(program_id, &auction.auction_id, auction.item_count);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, auction.item_count, mint);
+    let (blacklist, _) = find_blacklist_address(program_id, depositor);
+    let mut accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(item, false),
+        AccountMeta::new(*depositor_token, false),
+        AccountMeta::new(item_vault, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(blacklist, false),
+    ];
+    if auction.is_co_listed {
+        let (co_listing, _) = find_co_listing_address(program_id, &auction.auction_id);
+        accounts.push(AccountMeta::new(co_listing, false));
+    }
+    accounts
+}
+
+/// Build a `BidTraditional` instruction against the auction's current state
+#[allow(clippy::too_many_arguments)]
+pub fn bid_traditional(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    previous_bidder_token: &Pubkey,
+    amount: u64,
+    memo: Option<&str>,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (state, _) = find_program_state_address(program_id);
+    let (receipt, _) = find_bid_receipt_address(program_id, &auction.auction_id, auction.bid_count);
+    let mut accounts = vec![
+        AccountMeta::new(*bidder, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*bidder_token, false),
+        AccountMeta::new(*previous_bidder_token, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(receipt, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.current_bidder, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, bidder, gates));
+    let (deposit, _) = find_bid_deposit_address(program_id, &auction.auction_id, bidder);
+    accounts.push(AccountMeta::new(deposit, false));
+    if auction.current_bidder != Pubkey::default() && auction.current_bidder != *bidder {
+        let (previous_deposit, _) =
+            find_bid_deposit_address(program_id, &auction.auction_id, &auction.current_bidder);
+        accounts.push(AccountMeta::new_readonly(previous_deposit, false));
+    }
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BidTraditional {
+            amount,
+            memo: memo.map(str::to_owned),
+        },
+        with_memo_account(with_event_accounts(accounts), memo),
+    )
+}
+
+/// Accounts shared by `BuyDutch` and `BidPenny`
+fn pay_dealer_accounts(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    dealer_token: &Pubkey,
+    gates: &GateAccounts,
+) -> Vec<AccountMeta> {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let (receipt, _) = find_bid_receipt_address(program_id, &auction.auction_id, auction.bid_count);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    let mut accounts = vec![
+        AccountMeta::new(*bidder, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(*bidder_token, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(receipt, false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new(dealer_stats, false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, bidder, gates));
+    with_fee_flags(&mut accounts, program_id, auction);
+    with_event_accounts(accounts)
+}
+
+/// Build a `BuyDutch` instruction against the auction's current state
+#[allow(clippy::too_many_arguments)]
+pub fn buy_dutch(
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    buyer_token: &Pubkey,
+    dealer_token: &Pubkey,
+    max_price: u64,
+    memo: Option<&str>,
+    gates: &GateAccounts,
+) -> Instruction {
+    let mut accounts =
+        pay_dealer_accounts(program_id, auction, buyer, buyer_token, dealer_token, gates);
+    with_charity(&mut accounts, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BuyDutch {
+            max_price,
+            memo: memo.map(str::to_owned),
+        },
+        with_memo_account(accounts, memo),
+    )
+}
+
+/// Build a `BidPenny` instruction against the auction's current state
+pub fn bid_penny(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    dealer_token: &Pubkey,
+    memo: Option<&str>,
+    gates: &GateAccounts,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BidPenny {
+            memo: memo.map(str::to_owned),
+        },
+        with_memo_account(
+            pay_dealer_accounts(
+                program_id,
+                auction,
+                bidder,
+                bidder_token,
+                dealer_token,
+                gates,
+            ),
+            memo,
+        ),
+    )
+}
+
+/// Build an `OpenBidSession` instruction
+pub fn open_bid_session(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    session_key: &Pubkey,
+    expires_at: i64,
+    credits: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (session, _) = find_bid_session_address(program_id, &auction.auction_id, bidder);
+    let session_credits =
+        spl_associated_token_account::get_associated_token_address(&session, &auction.payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::OpenBidSession {
+            session_key: *session_key,
+            expires_at,
+            credits,
+        },
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(session, false),
+            AccountMeta::new(session_credits, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new_readonly(auction.payment_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `BidPennySession` instruction against the auction's current state
+pub fn bid_penny_session(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    session_key: &Pubkey,
+    dealer_token: &Pubkey,
+    memo: Option<&str>,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (session, _) = find_bid_session_address(program_id, &auction.auction_id, bidder);
+    let session_credits =
+        spl_associated_token_account::get_associated_token_address(&session, &auction.payment_mint);
+    // Gates are derived for the bidder; the session key signs in their place
+    // and the session PDA goes before the gate accounts
+    let mut accounts = pay_dealer_accounts(
+        program_id,
+        auction,
+        bidder,
+        &session_credits,
+        dealer_token,
+        gates,
+    );
+    accounts[0] = AccountMeta::new(*session_key, true);
+    accounts.insert(15, AccountMeta::new(session, false));
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BidPennySession {
+            memo: memo.map(str::to_owned),
+        },
+        with_memo_account(accounts, memo),
+    )
+}
+
+/// Build a `CloseBidSession` instruction
+pub fn close_bid_session(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (session, _) = find_bid_session_address(program_id, &auction.auction_id, bidder);
+    let session_credits =
+        spl_associated_token_account::get_associated_token_address(&session, &auction.payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CloseBidSession,
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(session, false),
+            AccountMeta::new(session_credits, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build a `FinalizeAuction` instruction
+pub fn finalize_auction(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    dealer_token: &Pubkey,
+    winner_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(*winner_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.current_bidder, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new(dealer_stats, false),
+    ];
+    if auction.current_bidder != Pubkey::default() {
+        let (winner_deposit, _) =
+            find_bid_deposit_address(program_id, &auction.auction_id, &auction.current_bidder);
+        accounts.push(AccountMeta::new_readonly(winner_deposit, false));
+    }
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::FinalizeAuction,
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build an `AcceptBid` instruction signed by the auction's dealer
+pub fn accept_bid(program_id: &Pubkey, auction: &Auction, dealer_token: &Pubkey) -> Instruction {
+    accept_bid_as(program_id, auction, &auction.dealer, dealer_token)
+}
+
+/// Build an `AcceptBid` instruction signed by `authority`, the dealer or
+/// their delegate
+pub fn accept_bid_as(
+    program_id: &Pubkey,
+    auction: &Auction,
+    authority: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(dealer_stats, false),
+    ];
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::AcceptBid,
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `RejectBid` instruction signed by the auction's dealer
+pub fn reject_bid(program_id: &Pubkey, auction: &Auction, winner_token: &Pubkey) -> Instruction {
+    reject_bid_as(program_id, auction, &auction.dealer, winner_token)
+}
+
+/// Build a `RejectBid` instruction signed by `authority`, the dealer or
+/// their delegate
+pub fn reject_bid_as(
+    program_id: &Pubkey,
+    auction: &Auction,
+    authority: &Pubkey,
+    winner_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (state, _) = find_program_state_address(program_id);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    let (winner_deposit, _) =
+        find_bid_deposit_address(program_id, &auction.auction_id, &auction.current_bidder);
+    let accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*winner_token, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(dealer_stats, false),
+        AccountMeta::new_readonly(auction.current_bidder, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(winner_deposit, false),
+    ];
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RejectBid,
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `CloseItemVault` instruction; `recipient` owns `recipient_token`
+#[allow(clippy::too_many_arguments)]
+pub fn close_item_vault(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    auction: &Auction,
+    item_index: u8,
+    item_mint: &Pubkey,
+    recipient: &Pubkey,
+    recipient_token: &Pubkey,
+    rent_recipient: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (item, _) = find_item_address(program_id, &auction.auction_id, item_index);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, item_index, item_mint);
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CloseItemVault { item_index },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new(item_vault, false),
+            AccountMeta::new(*recipient_token, false),
+            AccountMeta::new(*rent_recipient, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new_readonly(*item_mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(state, false),
+        ],
+    )
+}
+
+/// Build a `RelistAuction` instruction moving `items`, all of `auction`'s
+/// items in index order, into `new_auction`
+pub fn relist_auction(
+    program_id: &Pubkey,
+    auction: &Auction,
+    new_auction: &Auction,
+    items: &[AuctionItem],
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (new_auction_address, _) = find_auction_address(program_id, &new_auction.auction_id);
+    let mut accounts = vec![
+        AccountMeta::new(auction.dealer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(new_auction_address, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for item in items {
+        let (item_account, _) = find_item_address(program_id, &auction.auction_id, item.index);
+        let (item_vault, _) = find_item_vault_address(program_id, auction, item.index, &item.mint);
+        let (new_item, _) = find_item_address(program_id, &new_auction.auction_id, item.index);
+        let (new_item_vault, _) =
+            find_item_vault_address(program_id, new_auction, item.index, &item.mint);
+        accounts.extend([
+            AccountMeta::new(item_account, false),
+            AccountMeta::new(item_vault, false),
+            AccountMeta::new(new_item, false),
+            AccountMeta::new(new_item_vault, false),
+            AccountMeta::new_readonly(item.mint, false),
+        ]);
+    }
+    new_instruction(*program_id, &AuctionInstruction::RelistAuction, accounts)
+}
+
+/// Build a `CreateSeries` instruction
+pub fn create_series(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    series_id: &[u8; 32],
+    lots: &[[u8; 32]],
+) -> Instruction {
+    let (series, _) = find_series_address(program_id, series_id);
+    let mut accounts = vec![
+        AccountMeta::new(*dealer, true),
+        AccountMeta::new(series, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(lots.iter().map(|lot| {
+        let (auction, _) = find_auction_address(program_id, lot);
+        AccountMeta::new(auction, false)
+    }));
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CreateSeries {
+            series_id: *series_id,
+            lots: lots.to_vec(),
+        },
+        accounts,
+    )
+}
+
+/// Build an `OpenNextLot` instruction
+pub fn open_next_lot(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    series_id: &[u8; 32],
+    current_lot: &[u8; 32],
+    next_lot: &[u8; 32],
+) -> Instruction {
+    let (series, _) = find_series_address(program_id, series_id);
+    let (current, _) = find_auction_address(program_id, current_lot);
+    let (next, _) = find_auction_address(program_id, next_lot);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::OpenNextLot,
+        vec![
+            AccountMeta::new_readonly(*caller, true),
+            AccountMeta::new(series, false),
+            AccountMeta::new_readonly(current, false),
+            AccountMeta::new(next, false),
+        ],
+    )
+}
+
+/// Build a `SetItemReserve` instruction
+pub fn set_item_reserve(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    item_index: u8,
+    reserve_price: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, item_index);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetItemReserve {
+            item_index,
+            reserve_price,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetItemRoyalty` instruction
+pub fn set_item_royalty(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    item_index: u8,
+    royalty_bps: u16,
+    beneficiary: &Pubkey,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, item_index);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetItemRoyalty {
+            item_index,
+            royalty_bps,
+            beneficiary: *beneficiary,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetClockSale` instruction
+pub fn set_clock_sale(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    supply: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, 0);
+    let (clock_sale, _) = find_clock_sale_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetClockSale { supply },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new(clock_sale, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `RegisterIntent` instruction against the auction's current state
+pub fn register_intent(
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    buyer_token: &Pubkey,
+    quantity: u64,
+    max_price: u64,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (clock_sale, _) = find_clock_sale_address(program_id, &auction.auction_id);
+    let (intent, _) = find_clock_intent_address(program_id, &auction.auction_id, buyer);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(clock_sale, false),
+        AccountMeta::new(intent, false),
+        AccountMeta::new(*buyer_token, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, buyer, gates));
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RegisterIntent {
+            quantity,
+            max_price,
+        },
+        accounts,
+    )
+}
+
+/// Build a `ClearClockSale` instruction against the auction's current state
+pub fn clear_clock_sale(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (clock_sale, _) = find_clock_sale_address(program_id, &auction.auction_id);
+    let (item, _) = find_item_address(program_id, &auction.auction_id, 0);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(clock_sale, false),
+        AccountMeta::new(item, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(*program_id, &AuctionInstruction::ClearClockSale, accounts)
+}
+
+/// Build a `SettleIntent` instruction delivering `buyer`'s units of the
+/// auction's `item_mint`
+pub fn settle_intent(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    buyer: &Pubkey,
+    item_mint: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (clock_sale, _) = find_clock_sale_address(program_id, &auction.auction_id);
+    let (intent, _) = find_clock_intent_address(program_id, &auction.auction_id, buyer);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, 0, item_mint);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let buyer_item_token =
+        spl_associated_token_account::get_associated_token_address(buyer, item_mint);
+    let buyer_payment_token =
+        spl_associated_token_account::get_associated_token_address(buyer, &auction.payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SettleIntent,
+        vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new_readonly(clock_sale, false),
+            AccountMeta::new(intent, false),
+            AccountMeta::new(*buyer, false),
+            AccountMeta::new(buyer_item_token, false),
+            AccountMeta::new(buyer_payment_token, false),
+            AccountMeta::new(item_vault, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*item_mint, false),
+            AccountMeta::new_readonly(auction.payment_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetBondingCurve` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn set_bonding_curve(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    supply: u64,
+    base_price: u64,
+    slope: u64,
+    exponent: u8,
+    sell_back: bool,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, 0);
+    let (curve, _) = find_bonding_curve_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetBondingCurve {
+            supply,
+            base_price,
+            slope,
+            exponent,
+            sell_back,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new(curve, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `BuyCurve` instruction against the auction's current state
+#[allow(clippy::too_many_arguments)]
+pub fn buy_curve(
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    buyer_token: &Pubkey,
+    dealer_token: &Pubkey,
+    item_mint: &Pubkey,
+    quantity: u64,
+    max_cost: u64,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (curve, _) = find_bonding_curve_address(program_id, &auction.auction_id);
+    let buyer_item_token =
+        spl_associated_token_account::get_associated_token_address(buyer, item_mint);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, 0, item_mint);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(curve, false),
+        AccountMeta::new(*buyer_token, false),
+        AccountMeta::new(buyer_item_token, false),
+        AccountMeta::new(item_vault, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(*item_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, buyer, gates));
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BuyCurve { quantity, max_cost },
+        accounts,
+    )
+}
+
+/// Build a `SellCurve` instruction returning units of `item_mint` from the
+/// seller's associated token account
+pub fn sell_curve(
+    program_id: &Pubkey,
+    auction: &Auction,
+    seller: &Pubkey,
+    seller_token: &Pubkey,
+    item_mint: &Pubkey,
+    quantity: u64,
+    min_refund: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (curve, _) = find_bonding_curve_address(program_id, &auction.auction_id);
+    let seller_item_token =
+        spl_associated_token_account::get_associated_token_address(seller, item_mint);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, 0, item_mint);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*seller, true),
+        AccountMeta::new_readonly(auction_address, false),
+        AccountMeta::new(curve, false),
+        AccountMeta::new(seller_item_token, false),
+        AccountMeta::new(item_vault, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*seller_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SellCurve {
+            quantity,
+            min_refund,
+        },
+        accounts,
+    )
+}
+
+/// Build a `CloseCurveSale` instruction against the auction's current state
+pub fn close_curve_sale(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (curve, _) = find_bonding_curve_address(program_id, &auction.auction_id);
+    let (item, _) = find_item_address(program_id, &auction.auction_id, 0);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(curve, false),
+        AccountMeta::new(item, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(*program_id, &AuctionInstruction::CloseCurveSale, accounts)
+}
+
+/// Build a `SetUnitSale` instruction
+pub fn set_unit_sale(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    supply: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, 0);
+    let (unit_sale, _) = find_unit_sale_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetUnitSale { supply },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new(unit_sale, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `BuyUnits` instruction against the auction's current state
+#[allow(clippy::too_many_arguments)]
+pub fn buy_units(
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    buyer_token: &Pubkey,
+    dealer_token: &Pubkey,
+    item_mint: &Pubkey,
+    quantity: u64,
+    max_price: u64,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (unit_sale, _) = find_unit_sale_address(program_id, &auction.auction_id);
+    let buyer_item_token =
+        spl_associated_token_account::get_associated_token_address(buyer, item_mint);
+    let (item_vault, _) = find_item_vault_address(program_id, auction, 0, item_mint);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(unit_sale, false),
+        AccountMeta::new(*buyer_token, false),
+        AccountMeta::new(buyer_item_token, false),
+        AccountMeta::new(item_vault, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(*item_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, buyer, gates));
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::BuyUnits {
+            quantity,
+            max_price,
+        },
+        accounts,
+    )
+}
+
+/// Build a `CloseUnitSale` instruction
+pub fn close_unit_sale(program_id: &Pubkey, auction_id: &[u8; 32], caller: &Pubkey) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (unit_sale, _) = find_unit_sale_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, 0);
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CloseUnitSale,
+        vec![
+            AccountMeta::new_readonly(*caller, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new_readonly(unit_sale, false),
+            AccountMeta::new(item, false),
+            AccountMeta::new_readonly(state, false),
+        ],
+    )
+}
+
+/// Build a `SetHarbergerListing` instruction
+pub fn set_harberger_listing(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    price: u64,
+    tax_bps: u16,
+    tax_period: i64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (item, _) = find_item_address(program_id, auction_id, 0);
+    let (listing, _) = find_harberger_listing_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetHarbergerListing {
+            price,
+            tax_bps,
+            tax_period,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new_readonly(item, false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetAssessedPrice` instruction
+pub fn set_assessed_price(
+    program_id: &Pubkey,
+    holder: &Pubkey,
+    auction_id: &[u8; 32],
+    holder_token: &Pubkey,
+    price: u64,
+    deposit: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (listing, _) = find_harberger_listing_address(program_id, auction_id);
+    let (escrow, _) = find_escrow_address(program_id, auction_id);
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetAssessedPrice { price, deposit },
+        vec![
+            AccountMeta::new_readonly(*holder, true),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new(*holder_token, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build a `ForceBuy` instruction taking the item from `holder`, paid into
+/// their associated token account
+#[allow(clippy::too_many_arguments)]
+pub fn force_buy(
+    program_id: &Pubkey,
+    auction: &Auction,
+    buyer: &Pubkey,
+    buyer_token: &Pubkey,
+    holder: &Pubkey,
+    max_price: u64,
+    assessed_price: u64,
+    deposit: u64,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (listing, _) = find_harberger_listing_address(program_id, &auction.auction_id);
+    let holder_token =
+        spl_associated_token_account::get_associated_token_address(holder, &auction.payment_mint);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(listing, false),
+        AccountMeta::new(*buyer_token, false),
+        AccountMeta::new(holder_token, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*holder, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    accounts.extend(bid_gate_accounts(program_id, auction, buyer, gates));
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ForceBuy {
+            max_price,
+            assessed_price,
+            deposit,
+        },
+        accounts,
+    )
+}
+
+/// Build a `CloseHarbergerListing` instruction for the listing's current
+/// `holder`
+pub fn close_harberger_listing(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    holder: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (listing, _) = find_harberger_listing_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let holder_token =
+        spl_associated_token_account::get_associated_token_address(holder, &auction.payment_mint);
+    let dealer_token = spl_associated_token_account::get_associated_token_address(
+        &auction.dealer,
+        &auction.payment_mint,
+    );
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(listing, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(holder_token, false),
+        AccountMeta::new(dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*holder, false),
+        AccountMeta::new_readonly(auction.dealer, false),
+        AccountMeta::new_readonly(auction.payment_mint, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CloseHarbergerListing,
+        accounts,
+    )
+}
+
+/// Build a `PlaceCollectionBid` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn place_collection_bid(
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    payment_mint: &Pubkey,
+    collection: &Pubkey,
+    price: u64,
+    quantity: u64,
+    expires_at: i64,
+) -> Instruction {
+    let (bid, _) = find_collection_bid_address(program_id, collection, bidder);
+    let bid_token = spl_associated_token_account::get_associated_token_address(&bid, payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::PlaceCollectionBid {
+            collection: *collection,
+            price,
+            quantity,
+            expires_at,
+        },
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new(bid, false),
+            AccountMeta::new(bid_token, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `CancelCollectionBid` instruction
+pub fn cancel_collection_bid(
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    payment_mint: &Pubkey,
+    collection: &Pubkey,
+) -> Instruction {
+    let (bid, _) = find_collection_bid_address(program_id, collection, bidder);
+    let bid_token = spl_associated_token_account::get_associated_token_address(&bid, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CancelCollectionBid {
+            collection: *collection,
+        },
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new(bid, false),
+            AccountMeta::new(bid_token, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build an `AcceptCollectionBid` instruction selling the auction's NFT of
+/// `item_mint` to `bidder`'s bid on `collection`, signed by `authority`,
+/// the dealer or their delegate
+#[allow(clippy::too_many_arguments)]
+pub fn accept_collection_bid(
+    program_id: &Pubkey,
+    auction: &Auction,
+    authority: &Pubkey,
+    item_mint: &Pubkey,
+    collection: &Pubkey,
+    bidder: &Pubkey,
+    dealer_token: &Pubkey,
+    min_price: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (item, _) = find_item_address(program_id, &auction.auction_id, 0);
+    let (metadata, _) = find_metadata_address(item_mint);
+    let (bid, _) = find_collection_bid_address(program_id, collection, bidder);
+    let bid_token =
+        spl_associated_token_account::get_associated_token_address(&bid, &auction.payment_mint);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (state, _) = find_program_state_address(program_id);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new_readonly(item, false),
+        AccountMeta::new_readonly(metadata, false),
+        AccountMeta::new(bid, false),
+        AccountMeta::new(bid_token, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(dealer_stats, false),
+    ];
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::AcceptCollectionBid { min_price },
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `SettleSignedBid` instruction submitting `bidder`'s order to bid
+/// `amount` until `expires_at` with `nonce` against the auction's current
+/// state
+///
+/// The transaction must include, before this instruction, the Ed25519
+/// program instruction verifying `bidder`'s signature over
+/// `SignedBidOrder::message`, and `bidder_token` must approve `bidder`'s
+/// `find_bid_delegate_address` PDA for at least `amount`.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_signed_bid(
+    program_id: &Pubkey,
+    auction: &Auction,
+    submitter: &Pubkey,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    previous_bidder_token: &Pubkey,
+    amount: u64,
+    expires_at: i64,
+    nonce: u64,
+    gates: &GateAccounts,
+) -> Instruction {
+    let (delegate, _) = find_bid_delegate_address(program_id, bidder);
+    // Gates and deposits are derived for the bidder; the submitter signs in
+    // their place and the sysvar and delegate go before the gate accounts
+    let mut accounts = bid_traditional(
+        program_id,
+        auction,
+        bidder,
+        bidder_token,
+        previous_bidder_token,
+        amount,
+        None,
+        gates,
+    )
+    .accounts;
+    accounts[0] = AccountMeta::new(*submitter, true);
+    accounts.insert(
+        12,
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    );
+    accounts.insert(13, AccountMeta::new(delegate, false));
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SettleSignedBid {
+            bidder: *bidder,
+            amount,
+            expires_at,
+            nonce,
+        },
+        accounts,
+    )
+}
+
+/// Build a `SetTypePaused` instruction
+pub fn set_type_paused(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    auction_type: AuctionTypeTag,
+    paused: bool,
+) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetTypePaused {
+            auction_type,
+            paused,
+        },
+    )
+}
+
+/// Build a `FreezeAuction` or `UnfreezeAuction` instruction
+pub fn set_frozen(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    auction_id: &[u8; 32],
+    frozen: bool,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let instruction = if frozen {
+        AuctionInstruction::FreezeAuction
+    } else {
+        AuctionInstruction::UnfreezeAuction
+    };
+    new_instruction(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(auction, false),
+        ],
+    )
+}
+
+/// Build an `AddToBlacklist` instruction
+pub fn add_to_blacklist(program_id: &Pubkey, owner: &Pubkey, wallet: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (blacklist, _) = find_blacklist_address(program_id, wallet);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::AddToBlacklist { wallet: *wallet },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(blacklist, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `RemoveFromBlacklist` instruction
+pub fn remove_from_blacklist(program_id: &Pubkey, owner: &Pubkey, wallet: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (blacklist, _) = find_blacklist_address(program_id, wallet);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RemoveFromBlacklist { wallet: *wallet },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(blacklist, false),
+        ],
+    )
+}
+
+/// Build an `EnableFeeBurn` instruction
+pub fn enable_fee_burn(program_id: &Pubkey, owner: &Pubkey, payment_mint: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (fee_burn, _) = find_fee_burn_address(program_id, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::EnableFeeBurn {
+            payment_mint: *payment_mint,
+        },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(fee_burn, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `DisableFeeBurn` instruction
+pub fn disable_fee_burn(program_id: &Pubkey, owner: &Pubkey, payment_mint: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (fee_burn, _) = find_fee_burn_address(program_id, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::DisableFeeBurn {
+            payment_mint: *payment_mint,
+        },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(fee_burn, false),
+        ],
+    )
+}
+
+/// Build an `AddFeeExemption` instruction
+pub fn add_fee_exemption(program_id: &Pubkey, owner: &Pubkey, dealer: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (fee_exemption, _) = find_fee_exemption_address(program_id, dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::AddFeeExemption { dealer: *dealer },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(fee_exemption, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `RemoveFeeExemption` instruction
+pub fn remove_fee_exemption(program_id: &Pubkey, owner: &Pubkey, dealer: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (fee_exemption, _) = find_fee_exemption_address(program_id, dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RemoveFeeExemption { dealer: *dealer },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(fee_exemption, false),
+        ],
+    )
+}
+
+/// Build a `SetFeeOverride` instruction
+pub fn set_fee_override(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    auction_id: &[u8; 32],
+    fee_bps: u16,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (fee_override, _) = find_fee_override_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetFeeOverride { fee_bps },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(fee_override, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `RemoveFeeOverride` instruction
+pub fn remove_fee_override(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    auction_id: &[u8; 32],
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (fee_override, _) = find_fee_override_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RemoveFeeOverride,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(fee_override, false),
+        ],
+    )
+}
+
+/// Build a `SetCuratedMode` instruction
+pub fn set_curated_mode(program_id: &Pubkey, owner: &Pubkey, curated: bool) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetCuratedMode { curated },
+    )
+}
+
+/// Build a `SetCreationPaused` instruction
+pub fn set_creation_paused(program_id: &Pubkey, owner: &Pubkey, paused: bool) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetCreationPaused { paused },
+    )
+}
+
+/// Build a `SetBiddingPaused` instruction
+pub fn set_bidding_paused(program_id: &Pubkey, owner: &Pubkey, paused: bool) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetBiddingPaused { paused },
+    )
+}
+
+/// Build a `SetClaimWindow` instruction
+pub fn set_claim_window(program_id: &Pubkey, owner: &Pubkey, claim_window: i64) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetClaimWindow { claim_window },
+    )
+}
+
+/// Build a `SetAcceptancePeriod` instruction
+pub fn set_acceptance_period(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acceptance_period: i64,
+) -> Instruction {
+    owner_state_instruction(
+        program_id,
+        owner,
+        &AuctionInstruction::SetAcceptancePeriod { acceptance_period },
+    )
+}
+
+/// Build a `RegisterDealer` instruction
+pub fn register_dealer(program_id: &Pubkey, dealer: &Pubkey) -> Instruction {
+    let (profile, _) = find_dealer_profile_address(program_id, dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::RegisterDealer,
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(profile, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetDealerApproval` instruction
+pub fn set_dealer_approval(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    dealer: &Pubkey,
+    approved: bool,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (profile, _) = find_dealer_profile_address(program_id, dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetDealerApproval { approved },
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(profile, false),
+        ],
+    )
+}
+
+/// Build a `SetBondRequirement` instruction
+pub fn set_bond_requirement(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    payment_mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (config, _) = find_bond_config_address(program_id, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetBondRequirement { amount },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `PostBond` instruction signed by the auction's dealer
+pub fn post_bond(program_id: &Pubkey, auction: &Auction, dealer_token: &Pubkey) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (bond, _) = find_bond_address(program_id, &auction.auction_id);
+    let (bond_vault, _) = find_bond_vault_address(program_id, &auction.auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::PostBond,
+        vec![
+            AccountMeta::new(auction.dealer, true),
+            AccountMeta::new(auction_address, false),
+            AccountMeta::new(bond, false),
+            AccountMeta::new(bond_vault, false),
+            AccountMeta::new(*dealer_token, false),
+            AccountMeta::new_readonly(auction.payment_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `WithdrawBond` instruction signed by the auction's dealer
+pub fn withdraw_bond(program_id: &Pubkey, auction: &Auction, dealer_token: &Pubkey) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (bond, _) = find_bond_address(program_id, &auction.auction_id);
+    let (bond_vault, _) = find_bond_vault_address(program_id, &auction.auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::WithdrawBond,
+        vec![
+            AccountMeta::new_readonly(auction.dealer, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(bond, false),
+            AccountMeta::new(bond_vault, false),
+            AccountMeta::new(*dealer_token, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build a `SlashBond` instruction
+pub fn slash_bond(program_id: &Pubkey, owner: &Pubkey, auction: &Auction) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (bond, _) = find_bond_address(program_id, &auction.auction_id);
+    let (bond_vault, _) = find_bond_vault_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SlashBond,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(state, false),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(bond, false),
+            AccountMeta::new(bond_vault, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(fee_vault_token, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(dealer_stats, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Dealer-signed instruction touching only the auction
+fn dealer_auction_instruction(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    instruction: &AuctionInstruction,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        instruction,
+        vec![
+            AccountMeta::new_readonly(*dealer, true),
+            AccountMeta::new(auction, false),
+        ],
+    )
+}
+
+/// Build an `ApproveDepositor` instruction
+pub fn approve_depositor(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    depositor: &Pubkey,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (co_listing, _) = find_co_listing_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ApproveDepositor {
+            depositor: *depositor,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(co_listing, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetAuctionDelegate` instruction
+pub fn set_auction_delegate(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    delegate: &Pubkey,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetAuctionDelegate {
+            delegate: *delegate,
+        },
+    )
+}
+
+/// Build a `SetUsdPricing` instruction
+pub fn set_usd_pricing(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction: &Auction,
+    price_feed: &Pubkey,
+    reserve_usd_cents: u64,
+    start_usd_cents: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (usd_pricing, _) = find_usd_pricing_address(program_id, &auction.auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetUsdPricing {
+            price_feed: *price_feed,
+            reserve_usd_cents,
+            start_usd_cents,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction_address, false),
+            AccountMeta::new(usd_pricing, false),
+            AccountMeta::new_readonly(auction.payment_mint, false),
+            AccountMeta::new_readonly(*price_feed, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetDutchOracle` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn set_dutch_oracle(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction: &Auction,
+    lot_mint: &Pubkey,
+    price_feed: &Pubkey,
+    premium_bps: u16,
+    lot_amount: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (dutch_oracle, _) = find_dutch_oracle_address(program_id, &auction.auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetDutchOracle {
+            price_feed: *price_feed,
+            premium_bps,
+            lot_amount,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction_address, false),
+            AccountMeta::new(dutch_oracle, false),
+            AccountMeta::new_readonly(auction.payment_mint, false),
+            AccountMeta::new_readonly(*lot_mint, false),
+            AccountMeta::new_readonly(*price_feed, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `RevealReserve` instruction
+pub fn reveal_reserve(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    reserve_price: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::RevealReserve {
+            reserve_price,
+            salt,
+        },
+    )
+}
+
+/// Build an `UpdateReservePrice` instruction
+pub fn update_reserve_price(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    reserve_price: u64,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::UpdateReservePrice { reserve_price },
+    )
+}
+
+/// Build a `SetBidIncrement` instruction
+pub fn set_bid_increment(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    increment: u64,
+    increment_bps: u16,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetBidIncrement {
+            increment,
+            increment_bps,
+        },
+    )
+}
+
+/// Build a `SetTickSize` instruction
+pub fn set_tick_size(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    tick_size: u32,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetTickSize { tick_size },
+    )
+}
+
+/// Build a `SetIncrementTable` instruction
+pub fn set_increment_table(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    tiers: &[IncrementTier],
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (increment_table, _) = find_increment_table_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetIncrementTable {
+            tiers: tiers.to_vec(),
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(increment_table, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetAllowlistRoot` instruction
+pub fn set_allowlist_root(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    root: [u8; 32],
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetAllowlistRoot { root },
+    )
+}
+
+/// Build a `ProveAllowlist` instruction
+pub fn prove_allowlist(
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+    auction_id: &[u8; 32],
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (pass, _) = find_allowlist_pass_address(program_id, auction_id, bidder);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ProveAllowlist { proof },
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(pass, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build an `UpdateMetadata` instruction
+pub fn update_metadata(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    category: u16,
+    metadata_uri: &str,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::UpdateMetadata {
+            category,
+            metadata_uri: metadata_uri.to_string(),
+        },
+    )
+}
+
+/// Build a `SetTokenGate` instruction
+pub fn set_token_gate(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    mint: &Pubkey,
+    min_balance: u64,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetTokenGate {
+            mint: *mint,
+            min_balance,
+        },
+    )
+}
+
+/// Build a `SetCollectionGate` instruction
+pub fn set_collection_gate(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    collection: &Pubkey,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetCollectionGate {
+            collection: *collection,
+        },
+    )
+}
+
+/// Build an `InitBidHistory` instruction
+pub fn init_bid_history(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (history, _) = find_bid_history_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::InitBidHistory,
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(history, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `WithdrawLosingBid` instruction
+pub fn withdraw_losing_bid(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (deposit, _) = find_bid_deposit_address(program_id, &auction.auction_id, bidder);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::WithdrawLosingBid,
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(auction_address, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new(deposit, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Build a `ClaimExpiredBidRefund` instruction for the auction's winning
+/// bidder
+pub fn claim_expired_bid_refund(
+    program_id: &Pubkey,
+    auction: &Auction,
+    bidder_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (deposit, _) =
+        find_bid_deposit_address(program_id, &auction.auction_id, &auction.current_bidder);
+    let (mint_stats, _) = find_mint_stats_address(program_id, &auction.payment_mint);
+    let (dealer_stats, _) = find_dealer_stats_address(program_id, &auction.dealer);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ClaimExpiredBidRefund,
+        with_event_accounts(vec![
+            AccountMeta::new(auction.current_bidder, true),
+            AccountMeta::new(auction_address, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*bidder_token, false),
+            AccountMeta::new(deposit, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(mint_stats, false),
+            AccountMeta::new(dealer_stats, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]),
+    )
+}
+
+/// Build a `SetArbiter` instruction
+pub fn set_arbiter(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    arbiter: &Pubkey,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetArbiter { arbiter: *arbiter },
+    )
+}
+
+/// Build an `OpenDispute` instruction signed by the dealer or winner
+pub fn open_dispute(program_id: &Pubkey, party: &Pubkey, auction_id: &[u8; 32]) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (dispute, _) = find_dispute_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::OpenDispute,
+        vec![
+            AccountMeta::new(*party, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(dispute, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `ResolveDispute` instruction signed by the auction's arbiter
+pub fn resolve_dispute(
+    program_id: &Pubkey,
+    auction: &Auction,
+    dealer_token: &Pubkey,
+    ruling: DisputeRuling,
+) -> Instruction {
+    let (dispute, _) = find_dispute_address(program_id, &auction.auction_id);
+    let mut accounts = sale_payout_accounts(program_id, auction, &auction.arbiter, dealer_token);
+    accounts.insert(2, AccountMeta::new(dispute, false));
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ResolveDispute { ruling },
+        accounts,
+    )
+}
+
+/// Build a `ReleaseProceeds` instruction
+pub fn release_proceeds(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ReleaseProceeds,
+        sale_payout_accounts(program_id, auction, caller, dealer_token),
+    )
+}
+
+/// Build a `SetDeliveryTimeout` instruction
+pub fn set_delivery_timeout(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    timeout: u32,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetDeliveryTimeout { timeout },
+    )
+}
+
+/// Build a `ConfirmDelivery` instruction signed by the winner
+pub fn confirm_delivery(
+    program_id: &Pubkey,
+    auction: &Auction,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ConfirmDelivery,
+        sale_payout_accounts(program_id, auction, &auction.current_bidder, dealer_token),
+    )
+}
+
+/// Accounts shared by `ResolveDispute`, `ReleaseProceeds` and
+/// `ConfirmDelivery`
+fn sale_payout_accounts(
+    program_id: &Pubkey,
+    auction: &Auction,
+    signer: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let mut accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    with_proceeds_vesting(&mut accounts, program_id, auction);
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    with_event_accounts(accounts)
+}
+
+/// Append the proceeds vesting PDA to the accounts paying out the sale of
+/// `auction`, if its proceeds vest
+fn with_proceeds_vesting(accounts: &mut Vec<AccountMeta>, program_id: &Pubkey, auction: &Auction) {
+    if auction.has_proceeds_vesting {
+        let (vesting, _) = find_proceeds_vesting_address(program_id, &auction.auction_id);
+        accounts.push(AccountMeta::new(vesting, false));
+    }
+}
+
+/// Append the fee burn PDA of the payment mint, the fee exemption PDA of
+/// the dealer and, if it has one, the auction's fee override PDA to the
+/// accounts of an instruction charging a fee on `auction`
+fn with_fee_flags(accounts: &mut Vec<AccountMeta>, program_id: &Pubkey, auction: &Auction) {
+    let (fee_burn, _) = find_fee_burn_address(program_id, &auction.payment_mint);
+    let (fee_exemption, _) = find_fee_exemption_address(program_id, &auction.dealer);
+    accounts.extend([
+        AccountMeta::new_readonly(fee_burn, false),
+        AccountMeta::new_readonly(fee_exemption, false),
+    ]);
+    if auction.has_fee_override {
+        let (fee_override, _) = find_fee_override_address(program_id, &auction.auction_id);
+        accounts.push(AccountMeta::new_readonly(fee_override, false));
+    }
+}
+
+/// Pass `payment_mint` writable to a fee-collecting instruction, as needed
+/// while its fees are burned
+pub fn with_fee_burn(mut instruction: Instruction, payment_mint: &Pubkey) -> Instruction {
+    match instruction
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *payment_mint)
+    {
+        Some(meta) => meta.is_writable = true,
+        None => instruction
+            .accounts
+            .push(AccountMeta::new(*payment_mint, false)),
+    }
+    instruction
+}
+
+/// Append `recipient`'s associated token account for the payment mint of
+/// `auction` and the recipient, followed by whichever of the payment mint,
+/// system program and associated token program are not passed yet, so the
+/// token account can be created if it is missing
+fn with_payout_recipient(accounts: &mut Vec<AccountMeta>, auction: &Auction, recipient: &Pubkey) {
+    let recipient_token = spl_associated_token_account::get_associated_token_address(
+        recipient,
+        &auction.payment_mint,
+    );
+    accounts.extend([
+        AccountMeta::new(recipient_token, false),
+        AccountMeta::new_readonly(*recipient, false),
+    ]);
+    for key in [
+        auction.payment_mint,
+        system_program::id(),
+        spl_associated_token_account::id(),
+    ] {
+        if !accounts.iter().any(|meta| meta.pubkey == key) {
+            accounts.push(AccountMeta::new_readonly(key, false));
+        }
+    }
+}
+
+/// Append the charity's token account and the charity to the accounts
+/// paying out the proceeds of `auction`, if it has a charity
+fn with_charity(accounts: &mut Vec<AccountMeta>, auction: &Auction) {
+    if auction.has_charity() {
+        with_payout_recipient(accounts, auction, &auction.charity);
+    }
+}
+
+/// Build a `SetCharity` instruction
+pub fn set_charity(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    charity: &Pubkey,
+    charity_bps: u16,
+) -> Instruction {
+    dealer_auction_instruction(
+        program_id,
+        dealer,
+        auction_id,
+        &AuctionInstruction::SetCharity {
+            charity: *charity,
+            charity_bps,
+        },
+    )
+}
+
+/// Build a `SetProceedsVesting` instruction
+pub fn set_proceeds_vesting(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    duration: u32,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (vesting, _) = find_proceeds_vesting_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetProceedsVesting { duration },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(vesting, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SetSettlementSwap` instruction
+pub fn set_settlement_swap(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    auction_id: &[u8; 32],
+    target_mint: &Pubkey,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (settlement_swap, _) = find_settlement_swap_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SetSettlementSwap {
+            target_mint: *target_mint,
+            max_slippage_bps,
+        },
+        vec![
+            AccountMeta::new(*dealer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(settlement_swap, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `SwapProceeds` instruction signed by the dealer, forwarding the
+/// Jupiter `route` instruction built for the auction's escrow
+pub fn swap_proceeds(
+    program_id: &Pubkey,
+    auction: &Auction,
+    target_token: &Pubkey,
+    route: Instruction,
+    quoted_out: u64,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let (fee_vault, _) = find_fee_vault_address(program_id, &auction.payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, &auction.payment_mint);
+    let (settlement_swap, _) = find_settlement_swap_address(program_id, &auction.auction_id);
+    let mut accounts = vec![
+        AccountMeta::new(auction.dealer, true),
+        AccountMeta::new(auction_address, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*target_token, false),
+        AccountMeta::new(fee_vault_token, false),
+        AccountMeta::new(fee_vault, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(settlement_swap, false),
+        AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+    ];
+    let route_accounts = route.accounts.len() as u8;
+    // The program signs for the escrow
+    accounts.extend(route.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != escrow,
+        ..meta
+    }));
+    with_charity(&mut accounts, auction);
+    with_fee_flags(&mut accounts, program_id, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::SwapProceeds {
+            route: route.data,
+            route_accounts,
+            quoted_out,
+        },
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `ClaimVested` instruction
+pub fn claim_vested(
+    program_id: &Pubkey,
+    auction: &Auction,
+    caller: &Pubkey,
+    dealer_token: &Pubkey,
+) -> Instruction {
+    let (auction_address, _) = find_auction_address(program_id, &auction.auction_id);
+    let (vesting, _) = find_proceeds_vesting_address(program_id, &auction.auction_id);
+    let (escrow, _) = find_escrow_address(program_id, &auction.auction_id);
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new_readonly(auction_address, false),
+        AccountMeta::new(vesting, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(*dealer_token, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    with_charity(&mut accounts, auction);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::ClaimVested,
+        with_event_accounts(accounts),
+    )
+}
+
+/// Build a `CloseBidReceipt` instruction
+pub fn close_bid_receipt(
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+    auction_id: &[u8; 32],
+    bid_index: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    let (receipt, _) = find_bid_receipt_address(program_id, auction_id, bid_index);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::CloseBidReceipt,
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(receipt, false),
+        ],
+    )
+}
+
+/// Build a `MigrateAuction` instruction
+pub fn migrate_auction(program_id: &Pubkey, payer: &Pubkey, auction_id: &[u8; 32]) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::MigrateAuction,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(auction, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build an `UpgradeProgramState` instruction
+pub fn upgrade_program_state(program_id: &Pubkey, owner: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::UpgradeProgramState,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(state, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Build a `GetVersion` instruction
+pub fn get_version(program_id: &Pubkey) -> Instruction {
+    let (state, _) = find_program_state_address(program_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::GetVersion,
+        vec![AccountMeta::new_readonly(state, false)],
+    )
+}
+
+/// Build a `GetAuctionInfo` instruction
+pub fn get_auction_info(program_id: &Pubkey, auction_id: &[u8; 32]) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, auction_id);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::GetAuctionInfo,
+        vec![AccountMeta::new_readonly(auction, false)],
+    )
+}
+
+/// Build an `InitFeeVault` instruction
+pub fn init_fee_vault(program_id: &Pubkey, payer: &Pubkey, payment_mint: &Pubkey) -> Instruction {
+    let (fee_vault, _) = find_fee_vault_address(program_id, payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(program_id, payment_mint);
+    new_instruction(
+        *program_id,
+        &AuctionInstruction::InitFeeVault {
+            payment_mint: *payment_mint,
+        },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(fee_vault_token, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
 }
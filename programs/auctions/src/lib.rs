@@ -2,26 +2,75 @@
 //!
 //! Supports Traditional, Dutch, and Penny auctions with SPL tokens and NFTs.
 
+#[cfg(feature = "anchor")]
+pub mod anchor;
+#[cfg(all(feature = "client", not(target_os = "solana")))]
+pub mod client;
+#[cfg(feature = "program")]
+pub mod cpi;
 pub mod error;
+#[cfg(feature = "program")]
+pub mod events;
+#[cfg(feature = "program")]
 pub mod instruction;
+#[cfg(feature = "program")]
+pub mod memo;
+pub mod metadata;
+pub mod oracle;
+pub mod pda;
+pub mod pricing;
+#[cfg(feature = "program")]
 pub mod processor;
+pub mod signed_bid;
 pub mod state;
+#[cfg(feature = "program")]
+pub mod swap;
 
+#[cfg(feature = "program")]
+use error::AuctionError;
+#[cfg(feature = "program")]
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::PrintProgramError,
+    pubkey::Pubkey,
 };
 
-#[cfg(not(feature = "no-entrypoint"))]
-entrypoint!(process_instruction);
+#[cfg(all(feature = "program", not(feature = "no-entrypoint")))]
+solana_program::entrypoint!(process_instruction);
+
+// Disclosure channels embedded in the deployed binary
+#[cfg(all(feature = "program", not(feature = "no-entrypoint")))]
+solana_security_txt::security_txt! {
+    name: "Auctions",
+    project_url: "https://github.com/johnqh/auctions_contracts",
+    contacts: "link:https://github.com/johnqh/auctions_contracts/security/advisories/new",
+    policy: "https://github.com/johnqh/auctions_contracts/security/policy",
+    source_code: "https://github.com/johnqh/auctions_contracts",
+    source_release: env!("CARGO_PKG_VERSION")
+}
 
 /// Program entrypoint
+#[cfg(feature = "program")]
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    processor::process_instruction(program_id, accounts, instruction_data)
+    if let Err(error) = processor::process_instruction(program_id, accounts, instruction_data) {
+        // Log the readable variant name alongside the raw custom code
+        error.print::<AuctionError>();
+        return Err(error);
+    }
+    Ok(())
 }
 
-// Program ID placeholder - replace with actual deployed program ID
+#[cfg(all(feature = "devnet", feature = "mainnet"))]
+compile_error!("the `devnet` and `mainnet` features select different program IDs");
+
+// Program ID placeholders - replace with the IDs deployed on each cluster.
+// Builds without a cluster feature use the localnet ID.
+#[cfg(feature = "devnet")]
+solana_program::declare_id!("AucT1onDevnetXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX");
+#[cfg(all(feature = "mainnet", not(feature = "devnet")))]
+solana_program::declare_id!("AucT1onMainnetXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX");
+#[cfg(not(any(feature = "devnet", feature = "mainnet")))]
 solana_program::declare_id!("AucT1onProgramXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX");
@@ -0,0 +1,58 @@
+//! SPL Memo forwarding for bids
+//!
+//! Bid memos are CPI'd to the SPL Memo program so marketplaces' order ids and
+//! user notes show up in explorers next to the bid. The instruction is built
+//! by hand, so the `spl-memo` crate is not pulled in as a dependency.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use crate::error::AuctionError;
+
+/// SPL Memo program ID
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Longest memo accepted on a bid, in bytes
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// Reject memos over `MAX_MEMO_LEN`
+pub fn check_memo(memo: &Option<String>) -> ProgramResult {
+    match memo {
+        Some(memo) if memo.len() > MAX_MEMO_LEN => Err(AuctionError::MemoTooLong.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Forward `memo` to the SPL Memo program, signed by `signer`.
+///
+/// The Memo program account may appear anywhere in `accounts`; it is only
+/// required when a memo is attached.
+pub fn forward<'a>(
+    accounts: &[AccountInfo<'a>],
+    signer: &AccountInfo<'a>,
+    memo: &Option<String>,
+) -> ProgramResult {
+    let Some(memo) = memo else {
+        return Ok(());
+    };
+
+    let memo_program = accounts
+        .iter()
+        .find(|account| *account.key == MEMO_PROGRAM_ID)
+        .ok_or(AuctionError::MemoProgramMissing)?;
+
+    invoke(
+        &Instruction {
+            program_id: MEMO_PROGRAM_ID,
+            accounts: vec![AccountMeta::new_readonly(*signer.key, true)],
+            data: memo.as_bytes().to_vec(),
+        },
+        &[signer.clone(), memo_program.clone()],
+    )
+}
@@ -0,0 +1,119 @@
+//! Minimal Metaplex Token Metadata reader
+//!
+//! Only the fields the program needs are decoded, so the full Metaplex crate
+//! is not pulled in as a dependency.
+
+use borsh::BorshDeserialize;
+use solana_program::{program_error::ProgramError, pubkey, pubkey::Pubkey};
+
+use crate::error::AuctionError;
+
+/// Metaplex Token Metadata program ID
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Seed prefix for metadata PDAs
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+/// Account key tag for `MetadataV1`
+const KEY_METADATA_V1: u8 = 4;
+
+/// Token standard of semi-fungible tokens (0 decimals, supply above 1)
+pub const TOKEN_STANDARD_FUNGIBLE_ASSET: u8 = 1;
+
+/// Metadata creator entry
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// Collection membership
+#[derive(BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+/// Subset of a Metaplex metadata account
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    /// Mint the metadata describes
+    pub mint: Pubkey,
+    /// Royalty in basis points
+    pub seller_fee_basis_points: u16,
+    /// Creators list
+    pub creators: Option<Vec<Creator>>,
+    /// Token standard (unset on metadata created before standards existed)
+    pub token_standard: Option<u8>,
+    /// Collection the NFT claims to belong to
+    pub collection: Option<Collection>,
+}
+
+impl Metadata {
+    /// Decode the leading fields of a metadata account
+    pub fn parse(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::decode(&mut &data[..]).map_err(|_| AuctionError::InvalidNftMetadata.into())
+    }
+
+    fn decode(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let key = u8::deserialize(buf)?;
+        if key != KEY_METADATA_V1 {
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+
+        let _update_authority = Pubkey::deserialize(buf)?;
+        let mint = Pubkey::deserialize(buf)?;
+        let _name = String::deserialize(buf)?;
+        let _symbol = String::deserialize(buf)?;
+        let _uri = String::deserialize(buf)?;
+        let seller_fee_basis_points = u16::deserialize(buf)?;
+        let creators = Option::<Vec<Creator>>::deserialize(buf)?;
+        let _primary_sale_happened = bool::deserialize(buf)?;
+        let _is_mutable = bool::deserialize(buf)?;
+        let _edition_nonce = Option::<u8>::deserialize(buf)?;
+        let token_standard = Option::<u8>::deserialize(buf)?;
+        let collection = Option::<Collection>::deserialize(buf)?;
+
+        Ok(Self {
+            mint,
+            seller_fee_basis_points,
+            creators,
+            token_standard,
+            collection,
+        })
+    }
+
+    /// Whether the metadata may describe a semi-fungible token
+    pub fn is_fungible_asset(&self) -> bool {
+        matches!(
+            self.token_standard,
+            None | Some(TOKEN_STANDARD_FUNGIBLE_ASSET)
+        )
+    }
+
+    /// Verified creator with the largest share (the first on ties), who is
+    /// paid the royalty
+    pub fn royalty_beneficiary(&self) -> Option<Pubkey> {
+        self.creators
+            .as_ref()?
+            .iter()
+            .rev()
+            .filter(|creator| creator.verified)
+            .max_by_key(|creator| creator.share)
+            .map(|creator| creator.address)
+    }
+
+    /// Whether the NFT is a verified member of `collection`
+    pub fn in_collection(&self, collection: &Pubkey) -> bool {
+        matches!(&self.collection, Some(c) if c.verified && c.key == *collection)
+    }
+}
+
+/// Derive the metadata PDA for a mint
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+}
@@ -0,0 +1,144 @@
+//! Minimal Pyth price account reader
+//!
+//! Only the aggregate price of a v2 price account is decoded, so the Pyth SDK
+//! is not pulled in as a dependency.
+
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey, pubkey::Pubkey,
+};
+
+use crate::error::AuctionError;
+
+/// Pyth oracle program ID (mainnet)
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Oldest price, in seconds, accepted for a conversion
+pub const MAX_PRICE_AGE: i64 = 60;
+
+/// Magic number opening every Pyth account
+const MAGIC: u32 = 0xa1b2_c3d4;
+
+/// Account layout version of price accounts
+const VERSION_2: u32 = 2;
+
+/// Account type tag of price accounts
+const ACCOUNT_TYPE_PRICE: u32 = 3;
+
+/// Aggregate status of a price currently being published
+const STATUS_TRADING: u32 = 1;
+
+/// Field offsets in a v2 price account
+const OFFSET_EXPO: usize = 20;
+const OFFSET_TIMESTAMP: usize = 96;
+const OFFSET_AGG_PRICE: usize = 208;
+const OFFSET_AGG_STATUS: usize = 224;
+
+/// Aggregate USD price of one whole token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceFeed {
+    /// Price mantissa, always positive
+    pub price: i64,
+    /// Power of ten scaling `price`
+    pub expo: i32,
+    /// Unix timestamp the aggregate was published at
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    /// Decode the aggregate of a trading price account
+    pub fn parse(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::decode(data).ok_or_else(|| AuctionError::InvalidPriceFeed.into())
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        let u32_at = |offset| read(data, offset).map(u32::from_le_bytes);
+        let i64_at = |offset| read(data, offset).map(i64::from_le_bytes);
+
+        if u32_at(0)? != MAGIC || u32_at(4)? != VERSION_2 || u32_at(8)? != ACCOUNT_TYPE_PRICE {
+            return None;
+        }
+        if u32_at(OFFSET_AGG_STATUS)? != STATUS_TRADING {
+            return None;
+        }
+        let price = i64_at(OFFSET_AGG_PRICE)?;
+        if price <= 0 {
+            return None;
+        }
+
+        Some(Self {
+            price,
+            expo: read(data, OFFSET_EXPO).map(i32::from_le_bytes)?,
+            publish_time: i64_at(OFFSET_TIMESTAMP)?,
+        })
+    }
+
+    /// Read the Pyth-owned price account `feed`, rejecting prices older than
+    /// `MAX_PRICE_AGE` at `now`
+    pub fn load(feed: &AccountInfo, now: i64) -> Result<Self, ProgramError> {
+        if *feed.owner != PYTH_PROGRAM_ID {
+            return Err(AuctionError::InvalidPriceFeed.into());
+        }
+        let price = Self::parse(&feed.data.borrow())?;
+        if now.saturating_sub(price.publish_time) > MAX_PRICE_AGE {
+            return Err(AuctionError::StalePrice.into());
+        }
+        Ok(price)
+    }
+
+    /// Base units of a token with `decimals` worth `usd_cents`, rounded up so
+    /// the seller never receives less than the USD amount
+    pub fn usd_cents_to_amount(&self, usd_cents: u64, decimals: u8) -> Result<u64, ProgramError> {
+        let pow10 = |exp: u32| 10u128.checked_pow(exp).ok_or(AuctionError::MathOverflow);
+
+        // usd_cents * 10^decimals / (100 * price * 10^expo)
+        let mut numerator = (usd_cents as u128)
+            .checked_mul(pow10(decimals as u32)?)
+            .ok_or(AuctionError::MathOverflow)?;
+        let mut denominator = 100 * self.price as u128;
+        if self.expo < 0 {
+            numerator = numerator
+                .checked_mul(pow10(self.expo.unsigned_abs())?)
+                .ok_or(AuctionError::MathOverflow)?;
+        } else {
+            denominator = denominator
+                .checked_mul(pow10(self.expo as u32)?)
+                .ok_or(AuctionError::MathOverflow)?;
+        }
+
+        let amount = numerator.div_ceil(denominator);
+        u64::try_from(amount).map_err(|_| AuctionError::MathOverflow.into())
+    }
+
+    /// Value of `amount` base units of the quoted token (with `decimals`) in
+    /// base units of a currency with `quote_decimals`, rounded down
+    pub fn value_of(
+        &self,
+        amount: u64,
+        decimals: u8,
+        quote_decimals: u8,
+    ) -> Result<u64, ProgramError> {
+        let pow10 = |exp: u32| 10u128.checked_pow(exp).ok_or(AuctionError::MathOverflow);
+
+        // amount * price * 10^expo * 10^quote_decimals / 10^decimals
+        let mut numerator = (amount as u128 * self.price as u128)
+            .checked_mul(pow10(quote_decimals as u32)?)
+            .ok_or(AuctionError::MathOverflow)?;
+        let mut denominator = pow10(decimals as u32)?;
+        if self.expo < 0 {
+            denominator = denominator
+                .checked_mul(pow10(self.expo.unsigned_abs())?)
+                .ok_or(AuctionError::MathOverflow)?;
+        } else {
+            numerator = numerator
+                .checked_mul(pow10(self.expo as u32)?)
+                .ok_or(AuctionError::MathOverflow)?;
+        }
+
+        u64::try_from(numerator / denominator).map_err(|_| AuctionError::MathOverflow.into())
+    }
+}
+
+/// `N` bytes of `data` at `offset`
+fn read<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    data.get(offset..offset + N)?.try_into().ok()
+}
@@ -0,0 +1,455 @@
+//! PDA seeds and address derivation
+//!
+//! Every program-derived address is `[SEED, &[PDA_VERSION], ...]`, except the
+//! singleton program state.
+
+use solana_program::pubkey::Pubkey;
+#[cfg(feature = "program")]
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::state::{Auction, ProgramState, PDA_VERSION};
+
+/// Seeds for auction PDA
+pub const AUCTION_SEED: &[u8] = b"auction";
+/// Seeds for escrow PDA
+pub const ESCROW_SEED: &[u8] = b"escrow";
+/// Seeds for item vault PDA
+pub const ITEM_VAULT_SEED: &[u8] = b"item_vault";
+/// Seeds for fee vault PDA
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+/// Seeds for item account PDA
+pub const ITEM_SEED: &[u8] = b"item";
+/// Seeds for blacklist entry PDA
+pub const BLACKLIST_SEED: &[u8] = b"blacklist";
+/// Seeds for fee burn PDA
+pub const FEE_BURN_SEED: &[u8] = b"fee_burn";
+/// Seeds for fee exemption PDA
+pub const FEE_EXEMPTION_SEED: &[u8] = b"fee_exemption";
+/// Seeds for fee override PDA
+pub const FEE_OVERRIDE_SEED: &[u8] = b"fee_override";
+/// Seeds for dealer profile PDA
+pub const DEALER_PROFILE_SEED: &[u8] = b"dealer_profile";
+/// Seeds for bond config PDA
+pub const BOND_CONFIG_SEED: &[u8] = b"bond_config";
+/// Seeds for dealer bond PDA
+pub const BOND_SEED: &[u8] = b"bond";
+/// Seeds for bond vault PDA
+pub const BOND_VAULT_SEED: &[u8] = b"bond_vault";
+/// Seeds for allowlist pass PDA
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+/// Seeds for bid history PDA
+pub const BID_HISTORY_SEED: &[u8] = b"bid_history";
+/// Seeds for bid receipt PDA
+pub const BID_RECEIPT_SEED: &[u8] = b"bid_receipt";
+/// Seeds for bid deposit PDA
+pub const BID_DEPOSIT_SEED: &[u8] = b"bid_deposit";
+/// Seeds for dispute PDA
+pub const DISPUTE_SEED: &[u8] = b"dispute";
+/// Seeds for proceeds vesting PDA
+pub const PROCEEDS_VESTING_SEED: &[u8] = b"proceeds_vesting";
+/// Seeds for settlement swap PDA
+pub const SETTLEMENT_SWAP_SEED: &[u8] = b"settlement_swap";
+/// Seeds for auction series PDA
+pub const SERIES_SEED: &[u8] = b"series";
+/// Seeds for clock sale PDA
+pub const CLOCK_SALE_SEED: &[u8] = b"clock_sale";
+/// Seeds for clock intent PDA
+pub const CLOCK_INTENT_SEED: &[u8] = b"clock_intent";
+/// Seeds for bonding curve PDA
+pub const BONDING_CURVE_SEED: &[u8] = b"bonding_curve";
+/// Seeds for unit sale PDA
+pub const UNIT_SALE_SEED: &[u8] = b"unit_sale";
+/// Seeds for Harberger listing PDA
+pub const HARBERGER_LISTING_SEED: &[u8] = b"harberger_listing";
+/// Seeds for collection bid PDA
+pub const COLLECTION_BID_SEED: &[u8] = b"collection_bid";
+/// Seeds for a bidder's bid delegate PDA spending their signed bids
+pub const BID_DELEGATE_SEED: &[u8] = b"bid_delegate";
+/// Seeds for dealer index PDA
+pub const DEALER_INDEX_SEED: &[u8] = b"dealer_index";
+/// Seeds for dealer index page PDA
+pub const DEALER_INDEX_PAGE_SEED: &[u8] = b"dealer_index_page";
+/// Seeds for registry page PDA
+pub const REGISTRY_PAGE_SEED: &[u8] = b"registry_page";
+/// Seeds for mint stats PDA
+pub const MINT_STATS_SEED: &[u8] = b"mint_stats";
+/// Seeds for dealer stats PDA
+pub const DEALER_STATS_SEED: &[u8] = b"dealer_stats";
+/// Seeds for bidder stats PDA
+pub const BIDDER_STATS_SEED: &[u8] = b"bidder_stats";
+/// Seeds for payout splits PDA
+pub const PAYOUT_SPLITS_SEED: &[u8] = b"payout_splits";
+/// Seeds for co-listing PDA
+pub const CO_LISTING_SEED: &[u8] = b"co_listing";
+/// Seeds for bid session PDA
+pub const BID_SESSION_SEED: &[u8] = b"bid_session";
+/// Seeds for USD pricing PDA
+pub const USD_PRICING_SEED: &[u8] = b"usd_pricing";
+/// Seeds for Dutch oracle PDA
+pub const DUTCH_ORACLE_SEED: &[u8] = b"dutch_oracle";
+/// Seeds for increment table PDA
+pub const INCREMENT_TABLE_SEED: &[u8] = b"increment_table";
+
+/// Derive the program state PDA
+pub fn find_program_state_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ProgramState::SEEDS], program_id)
+}
+
+/// Derive an auction PDA
+pub fn find_auction_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive an auction's payment escrow token account
+pub fn find_escrow_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the item account at `index` in an auction
+pub fn find_item_address(program_id: &Pubkey, auction_id: &[u8; 32], index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ITEM_SEED, &[PDA_VERSION], auction_id, &[index]],
+        program_id,
+    )
+}
+
+/// Derive the vault token account holding the item at `index` of `mint` in
+/// an auction
+pub fn find_item_vault_address(
+    program_id: &Pubkey,
+    auction: &Auction,
+    index: u8,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ITEM_VAULT_SEED,
+            &[PDA_VERSION],
+            &auction.auction_id,
+            auction.item_vault_seed(&index, mint),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the fee vault for a payment mint
+pub fn find_fee_vault_address(program_id: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_VAULT_SEED, &[PDA_VERSION], payment_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the fee vault's token account, the fee vault PDA's associated
+/// token account for `payment_mint`
+#[cfg(feature = "program")]
+pub fn find_fee_vault_token_address(program_id: &Pubkey, payment_mint: &Pubkey) -> Pubkey {
+    let (fee_vault, _) = find_fee_vault_address(program_id, payment_mint);
+    get_associated_token_address(&fee_vault, payment_mint)
+}
+
+/// Derive the blacklist entry for a wallet
+pub fn find_blacklist_address(program_id: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BLACKLIST_SEED, &[PDA_VERSION], wallet.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the fee burn flag of a payment mint
+pub fn find_fee_burn_address(program_id: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_BURN_SEED, &[PDA_VERSION], payment_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive a dealer's fee exemption
+pub fn find_fee_exemption_address(program_id: &Pubkey, dealer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_EXEMPTION_SEED, &[PDA_VERSION], dealer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the fee override of an auction
+pub fn find_fee_override_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_OVERRIDE_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive a dealer's profile
+pub fn find_dealer_profile_address(program_id: &Pubkey, dealer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DEALER_PROFILE_SEED, &[PDA_VERSION], dealer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive a dealer's auction index
+pub fn find_dealer_index_address(program_id: &Pubkey, dealer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DEALER_INDEX_SEED, &[PDA_VERSION], dealer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive page `page` of a dealer's auction index
+pub fn find_dealer_index_page_address(
+    program_id: &Pubkey,
+    dealer: &Pubkey,
+    page: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DEALER_INDEX_PAGE_SEED,
+            &[PDA_VERSION],
+            dealer.as_ref(),
+            &page.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive page `page` of the global auction registry
+pub fn find_registry_page_address(program_id: &Pubkey, page: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REGISTRY_PAGE_SEED, &[PDA_VERSION], &page.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derive the protocol statistics for a payment mint
+pub fn find_mint_stats_address(program_id: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MINT_STATS_SEED, &[PDA_VERSION], payment_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the track record of a dealer
+pub fn find_dealer_stats_address(program_id: &Pubkey, dealer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DEALER_STATS_SEED, &[PDA_VERSION], dealer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the opt-in activity totals of a bidder
+pub fn find_bidder_stats_address(program_id: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BIDDER_STATS_SEED, &[PDA_VERSION], bidder.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the payout splits of an auction
+pub fn find_payout_splits_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_SPLITS_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive the co-listing of an auction
+pub fn find_co_listing_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CO_LISTING_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the USD pricing of an auction
+pub fn find_usd_pricing_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USD_PRICING_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the Dutch oracle of an auction
+pub fn find_dutch_oracle_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DUTCH_ORACLE_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the dispute over an auction's sale
+pub fn find_dispute_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DISPUTE_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the proceeds vesting schedule of an auction
+pub fn find_proceeds_vesting_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROCEEDS_VESTING_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive the settlement swap of an auction's proceeds
+pub fn find_settlement_swap_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SETTLEMENT_SWAP_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive an auction series
+pub fn find_series_address(program_id: &Pubkey, series_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SERIES_SEED, &[PDA_VERSION], series_id], program_id)
+}
+
+/// Derive the clock sale of an auction
+pub fn find_clock_sale_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLOCK_SALE_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the clock intent of `buyer` on an auction
+pub fn find_clock_intent_address(
+    program_id: &Pubkey,
+    auction_id: &[u8; 32],
+    buyer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CLOCK_INTENT_SEED,
+            &[PDA_VERSION],
+            auction_id,
+            buyer.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the bonding curve of an auction
+pub fn find_bonding_curve_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BONDING_CURVE_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive the unit sale of an auction
+pub fn find_unit_sale_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UNIT_SALE_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the Harberger listing of an auction
+pub fn find_harberger_listing_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[HARBERGER_LISTING_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive the increment table of an auction
+pub fn find_increment_table_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[INCREMENT_TABLE_SEED, &[PDA_VERSION], auction_id],
+        program_id,
+    )
+}
+
+/// Derive the escrowed deposit of `bidder` on an auction
+pub fn find_bid_deposit_address(
+    program_id: &Pubkey,
+    auction_id: &[u8; 32],
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_DEPOSIT_SEED,
+            &[PDA_VERSION],
+            auction_id,
+            bidder.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the bid session of `bidder` on an auction
+pub fn find_bid_session_address(
+    program_id: &Pubkey,
+    auction_id: &[u8; 32],
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_SESSION_SEED,
+            &[PDA_VERSION],
+            auction_id,
+            bidder.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the delegate `bidder` approves on their token account so their
+/// signed bids can be paid from it
+pub fn find_bid_delegate_address(program_id: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BID_DELEGATE_SEED, &[PDA_VERSION], bidder.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive a bidder's standing bid on a collection
+pub fn find_collection_bid_address(
+    program_id: &Pubkey,
+    collection: &Pubkey,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            COLLECTION_BID_SEED,
+            &[PDA_VERSION],
+            collection.as_ref(),
+            bidder.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the bond config for a payment mint
+pub fn find_bond_config_address(program_id: &Pubkey, payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BOND_CONFIG_SEED, &[PDA_VERSION], payment_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive the dealer bond for an auction
+pub fn find_bond_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BOND_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the token account holding an auction's dealer bond
+pub fn find_bond_vault_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BOND_VAULT_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive a bidder's allowlist pass for an auction
+pub fn find_allowlist_pass_address(
+    program_id: &Pubkey,
+    auction_id: &[u8; 32],
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ALLOWLIST_SEED, &[PDA_VERSION], auction_id, bidder.as_ref()],
+        program_id,
+    )
+}
+
+/// Derive an auction's bid history
+pub fn find_bid_history_address(program_id: &Pubkey, auction_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_HISTORY_SEED, &[PDA_VERSION], auction_id], program_id)
+}
+
+/// Derive the receipt for the bid at `bid_index`
+pub fn find_bid_receipt_address(
+    program_id: &Pubkey,
+    auction_id: &[u8; 32],
+    bid_index: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_RECEIPT_SEED,
+            &[PDA_VERSION],
+            auction_id,
+            &bid_index.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
@@ -0,0 +1,7 @@
+//! Pricing math shared with off-chain clients
+//!
+//! Re-exports the `auctions-pricing` crate, which builds `no_std` without
+//! dependencies so wasm frontends price auctions from the same code the
+//! program runs. `state` re-exports everything here.
+
+pub use auctions_pricing::*;
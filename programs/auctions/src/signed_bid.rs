@@ -0,0 +1,90 @@
+//! Off-chain signed bid orders
+//!
+//! A bidder signs an order off-chain and anyone may submit it with
+//! `SettleSignedBid`. The signature is checked by an Ed25519 program
+//! instruction earlier in the same transaction, which the program finds
+//! through the instructions sysvar, so no signature code runs on-chain.
+
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, entrypoint::ProgramResult, pubkey::Pubkey,
+    sysvar::instructions,
+};
+
+use crate::error::AuctionError;
+
+/// Prefix of every signed bid message, so the signature cannot be replayed
+/// as some other message
+pub const SIGNED_BID_DOMAIN: &[u8] = b"auctions:signed-bid:v1";
+
+/// Size of one signature's offsets in an Ed25519 program instruction
+const OFFSETS_LEN: usize = 14;
+
+/// Instruction index meaning "this instruction" in Ed25519 offsets
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Bid a bidder signs off-chain: `amount` on `auction_id`, valid until
+/// `expires_at`. `nonce` must be their bid delegate's current nonce, so the
+/// order settles at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedBidOrder {
+    pub auction_id: [u8; 32],
+    pub amount: u64,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+impl SignedBidOrder {
+    /// Bytes the bidder signs, bound to the deployment at `program_id`
+    pub fn message(&self, program_id: &Pubkey) -> Vec<u8> {
+        let mut message = Vec::with_capacity(SIGNED_BID_DOMAIN.len() + 32 + 32 + 8 + 8 + 8);
+        message.extend_from_slice(SIGNED_BID_DOMAIN);
+        message.extend_from_slice(program_id.as_ref());
+        message.extend_from_slice(&self.auction_id);
+        message.extend_from_slice(&self.amount.to_le_bytes());
+        message.extend_from_slice(&self.expires_at.to_le_bytes());
+        message.extend_from_slice(&self.nonce.to_le_bytes());
+        message
+    }
+
+    /// Whether the order has lapsed at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.expires_at
+    }
+}
+
+/// Check that an Ed25519 program instruction before the current one verified
+/// `signer`'s signature over `message`
+pub fn verify(instructions_sysvar: &AccountInfo, signer: &Pubkey, message: &[u8]) -> ProgramResult {
+    let current = instructions::load_current_index_checked(instructions_sysvar)?;
+    for index in 0..current {
+        let ix = instructions::load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if ix.program_id == ed25519_program::id() && signs(&ix.data, signer, message) {
+            return Ok(());
+        }
+    }
+    Err(AuctionError::InvalidSignedBid.into())
+}
+
+/// Whether Ed25519 program instruction `data` holds a signature by `signer`
+/// over `message`. Only signatures whose parts all sit in that instruction
+/// count.
+fn signs(data: &[u8], signer: &Pubkey, message: &[u8]) -> bool {
+    let Some(&count) = data.first() else {
+        return false;
+    };
+    (0..count as usize).any(|i| {
+        let start = 2 + i * OFFSETS_LEN;
+        let Some(offsets) = data.get(start..start + OFFSETS_LEN) else {
+            return false;
+        };
+        let field = |n: usize| u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]);
+        let (signature_ix, key_offset, key_ix) = (field(1), field(2) as usize, field(3));
+        let (message_offset, message_size, message_ix) =
+            (field(4) as usize, field(5) as usize, field(6));
+        if [signature_ix, key_ix, message_ix] != [CURRENT_INSTRUCTION; 3] {
+            return false;
+        }
+        data.get(key_offset..key_offset + 32) == Some(signer.as_ref())
+            && data.get(message_offset..message_offset + message_size) == Some(message)
+    })
+}
@@ -1,28 +1,170 @@
 //! Program state definitions
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use bytemuck::{CheckedBitPattern, NoUninit, Zeroable};
+use solana_program::{
+    entrypoint::ProgramResult, keccak, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{error::AuctionError, oracle::PriceFeed};
+
+pub use crate::pricing::{
+    calculate_dutch_price, calculate_fee, calculate_fee_at, dutch_price_schedule, min_bid,
+    next_dutch_price_change, DutchParams, PennyParams, TraditionalParams, FEE_DENOMINATOR,
+    FEE_RATE,
+};
 
 /// PDA version for future upgrades
 pub const PDA_VERSION: u8 = 1;
 
-/// 24 hours in seconds for dealer acceptance window
-pub const ACCEPTANCE_PERIOD: i64 = 24 * 60 * 60;
+/// 24 hours in seconds for the dealer's acceptance window, until the owner
+/// configures `ProgramState::acceptance_period`
+pub const DEFAULT_ACCEPTANCE_PERIOD: i64 = 24 * 60 * 60;
+
+/// 1 hour in seconds, the shortest acceptance window the owner can set
+pub const MIN_ACCEPTANCE_PERIOD: i64 = 60 * 60;
+
+/// 7 days in seconds, the longest acceptance window the owner can set
+pub const MAX_ACCEPTANCE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// 3 days in seconds after finalization in which either party of an
+/// arbitrated sale can open a dispute
+pub const DISPUTE_WINDOW: i64 = 3 * 24 * 60 * 60;
+
+/// 30 days in seconds a winner has to claim items before the dealer may
+/// reclaim them, until the owner configures `ProgramState::claim_window`
+pub const DEFAULT_CLAIM_WINDOW: i64 = 30 * 24 * 60 * 60;
+
+/// 7 days in seconds after its deadline before a no-bid auction can be swept
+pub const SWEEP_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// 24 hours in seconds, the longest a bid session key stays valid
+pub const MAX_SESSION_DURATION: i64 = 24 * 60 * 60;
 
-/// 5 minutes in seconds for Penny auction timer
+/// 5 minutes in seconds for Penny auction timer, used when a dealer passes
+/// zero
 pub const PENNY_TIMER_DURATION: i64 = 5 * 60;
 
-/// Fee rate in basis points (0.5% = 50)
-pub const FEE_RATE: u64 = 50;
+/// 30 seconds, the shortest Penny auction timer a dealer can set
+pub const MIN_PENNY_TIMER_DURATION: i64 = 30;
+
+/// 1 hour in seconds, the longest Penny auction timer a dealer can set
+pub const MAX_PENNY_TIMER_DURATION: i64 = 60 * 60;
+
+/// Current `ProgramState` schema version
+pub const PROGRAM_STATE_VERSION: u8 = 5;
+
+/// Current `Auction` account layout version
+pub const AUCTION_LAYOUT_VERSION: u8 = 10;
+
+/// Length of the account type discriminator prefix
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// Program-owned account prefixed with an 8-byte type discriminator
+///
+/// Discriminators follow the Anchor convention (`sha256("account:<Name>")[..8]`)
+/// so one account type can never be deserialized as another.
+pub trait ProgramAccount: BorshSerialize + BorshDeserialize {
+    /// Account type discriminator
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+
+    /// Deserialize account data, rejecting foreign discriminators
+    fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < DISCRIMINATOR_LEN || data[..DISCRIMINATOR_LEN] != Self::DISCRIMINATOR {
+            return Err(AuctionError::InvalidAccountDiscriminator.into());
+        }
+        Ok(Self::deserialize(&mut &data[DISCRIMINATOR_LEN..])?)
+    }
+
+    /// Write the discriminator followed by the serialized account
+    fn store(&self, data: &mut [u8]) -> ProgramResult {
+        if data.len() < DISCRIMINATOR_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..DISCRIMINATOR_LEN].copy_from_slice(&Self::DISCRIMINATOR);
+        borsh::to_writer(&mut data[DISCRIMINATOR_LEN..], self)?;
+        Ok(())
+    }
+}
+
+/// Fixed-layout program account accessed through bytemuck
+///
+/// Used for accounts touched on every bid, where borsh decoding and
+/// re-encoding the whole account is wasted compute. The bytes after the
+/// discriminator are the `repr(C)` struct itself.
+pub trait PodAccount: CheckedBitPattern + NoUninit {
+    /// Account type discriminator
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+
+    /// Fails to compile for a layout the discriminator would misalign. The
+    /// runtime aligns account data to 8 bytes, so fields no wider than that
+    /// stay aligned after the 8-byte discriminator.
+    const ALIGNED: () = assert!(
+        std::mem::align_of::<Self>() <= 8
+            && DISCRIMINATOR_LEN.is_multiple_of(std::mem::align_of::<Self>())
+    );
+
+    /// Borrow the account in place, rejecting foreign discriminators, invalid
+    /// bit patterns and misaligned data
+    fn load_ref(data: &[u8]) -> Result<&Self, ProgramError> {
+        let () = Self::ALIGNED;
+        if data.len() < DISCRIMINATOR_LEN || data[..DISCRIMINATOR_LEN] != Self::DISCRIMINATOR {
+            return Err(AuctionError::InvalidAccountDiscriminator.into());
+        }
+        let bytes = data
+            .get(DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + std::mem::size_of::<Self>())
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        bytemuck::checked::try_from_bytes(bytes).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Borrow the account mutably in place, with the checks of `load_ref`
+    fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let () = Self::ALIGNED;
+        if data.len() < DISCRIMINATOR_LEN || data[..DISCRIMINATOR_LEN] != Self::DISCRIMINATOR {
+            return Err(AuctionError::InvalidAccountDiscriminator.into());
+        }
+        let bytes = data
+            .get_mut(DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + std::mem::size_of::<Self>())
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        bytemuck::checked::try_from_bytes_mut(bytes).map_err(|_| ProgramError::InvalidAccountData)
+    }
 
-/// Fee denominator (basis points)
-pub const FEE_DENOMINATOR: u64 = 10000;
+    /// Read a copy of the account, for handlers that write it back after
+    /// CPIs that borrow the account list
+    fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::load_ref(data).copied()
+    }
+
+    /// Write the discriminator followed by the raw account bytes
+    fn store(&self, data: &mut [u8]) -> ProgramResult {
+        let end = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+        if data.len() < end {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..DISCRIMINATOR_LEN].copy_from_slice(&Self::DISCRIMINATOR);
+        data[DISCRIMINATOR_LEN..end].copy_from_slice(bytemuck::bytes_of(self));
+        Ok(())
+    }
+}
 
 /// Auction status
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    CheckedBitPattern,
+    NoUninit,
+    Zeroable,
+)]
 #[repr(u8)]
 pub enum AuctionStatus {
     /// Auction is live and accepting bids
+    #[default]
     Active = 0,
     /// Deadline passed, pending finalization or acceptance
     Expired = 1,
@@ -32,89 +174,52 @@ pub enum AuctionStatus {
     Refunded = 3,
 }
 
-impl Default for AuctionStatus {
-    fn default() -> Self {
-        Self::Active
-    }
-}
-
 /// Auction type tag for quick filtering
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    CheckedBitPattern,
+    NoUninit,
+    Zeroable,
+)]
 #[repr(u8)]
 pub enum AuctionTypeTag {
+    #[default]
     Traditional = 0,
     Dutch = 1,
     Penny = 2,
 }
 
-impl Default for AuctionTypeTag {
-    fn default() -> Self {
-        Self::Traditional
-    }
-}
+/// Size of the type-specific parameter block in an `Auction`, in 8-byte words
+pub const AUCTION_PARAMS_WORDS: usize = 6;
 
-/// Traditional auction parameters
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
-pub struct TraditionalParams {
-    /// Minimum first bid
-    pub start_amount: u64,
-    /// Minimum bid increase
-    pub increment: u64,
-    /// Minimum price to auto-finalize
-    pub reserve_price: u64,
-    /// Auction end timestamp
-    pub deadline: i64,
-    /// Dealer acceptance deadline (24h after auction end)
-    pub acceptance_deadline: i64,
-    /// Whether reserve price was met
-    pub reserve_met: bool,
-}
+/// Most payout splits an auction can configure
+pub const MAX_PAYOUT_SPLITS: usize = 4;
 
-/// Dutch auction parameters
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
-pub struct DutchParams {
-    /// Initial price
-    pub start_price: u64,
-    /// Price decrease per interval
-    pub decrease_amount: u64,
-    /// Seconds between decreases
-    pub interval: i64,
-    /// Floor price
-    pub minimum_price: u64,
-    /// Auction end timestamp
-    pub deadline: i64,
-    /// When price starts decreasing
-    pub start_time: i64,
-}
+/// Most tiers an auction's increment table can hold
+pub const MAX_INCREMENT_TIERS: usize = 8;
 
-/// Penny auction parameters
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
-pub struct PennyParams {
-    /// Fixed bid increment (paid to dealer)
-    pub increment: u64,
-    /// Timer reset duration (typically 5 minutes)
-    pub timer_duration: i64,
-    /// Current deadline (resets on each bid)
-    pub current_deadline: i64,
-    /// Running total of payments to dealer
-    pub total_paid: u64,
-    /// Timestamp of last bid
-    pub last_bid_time: i64,
-}
+/// Most depositors besides the dealer a co-listed auction can approve
+pub const MAX_CO_DEPOSITORS: usize = 4;
 
-/// Auction type with embedded parameters
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
-pub enum AuctionType {
-    Traditional(TraditionalParams),
-    Dutch(DutchParams),
-    Penny(PennyParams),
-}
+/// Longest metadata URI an `Auction` can hold, in bytes
+pub const MAX_METADATA_URI_LEN: usize = 200;
 
-impl Default for AuctionType {
-    fn default() -> Self {
-        Self::Traditional(TraditionalParams::default())
-    }
-}
+/// Most lots an `AuctionSeries` can chain
+pub const MAX_SERIES_LOTS: usize = 16;
+
+/// Highest power of units sold a `BondingCurve` can price by
+pub const MAX_CURVE_EXPONENT: u8 = 3;
+
+/// Divisor of a `BondingCurve` slope, so slopes can be fractions of a base
+/// unit of the payment mint
+pub const CURVE_SLOPE_SCALE: u128 = 1_000_000;
 
 /// Global program state - singleton
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
@@ -129,66 +234,742 @@ pub struct ProgramState {
     pub bump: u8,
     /// Initialized flag
     pub is_initialized: bool,
+    /// Traditional auctions paused independently of the global flag
+    pub traditional_paused: bool,
+    /// Dutch auctions paused independently of the global flag
+    pub dutch_paused: bool,
+    /// Penny auctions paused independently of the global flag
+    pub penny_paused: bool,
+    /// When set, only dealers with an approved profile may create auctions
+    pub curated: bool,
+    /// Schema version (`PROGRAM_STATE_VERSION` when written; accounts
+    /// created before versioning read as 0)
+    pub schema_version: u8,
+    /// Seconds after finalization a winner has to claim items before the
+    /// dealer may reclaim them (schema 2)
+    pub claim_window: i64,
+    /// Seconds after the deadline a dealer has to accept a bid below the
+    /// reserve, fixed on each auction at creation (schema 3)
+    pub acceptance_period: i64,
+    /// Blocks creating auctions while live ones run to completion (schema 4)
+    pub creation_paused: bool,
+    /// Blocks bids and purchases while finalization, refunds and claims keep
+    /// working (schema 5)
+    pub bidding_paused: bool,
 }
 
 impl ProgramState {
     /// Account size
-    pub const LEN: usize = 32 + 1 + 8 + 1 + 1; // 43 bytes
+    pub const LEN: usize = 32 + 1 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 1 + 1; // 66 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
 
     /// Seeds for PDA derivation
     pub const SEEDS: &'static [u8] = b"auction_state";
+
+    /// Whether creating and bidding on the given auction type are halted,
+    /// either globally or through the type-specific flag
+    pub fn is_type_paused(&self, auction_type: AuctionTypeTag) -> bool {
+        self.paused
+            || match auction_type {
+                AuctionTypeTag::Traditional => self.traditional_paused,
+                AuctionTypeTag::Dutch => self.dutch_paused,
+                AuctionTypeTag::Penny => self.penny_paused,
+            }
+    }
+
+    /// Whether the winner's claim on items of an auction finalized at
+    /// `finalized_at` has lapsed by `now`
+    pub fn claim_lapsed(&self, finalized_at: i64, now: i64) -> bool {
+        now > finalized_at.saturating_add(self.claim_window)
+    }
+
+    /// Set the type-specific pause flag
+    pub fn set_type_paused(&mut self, auction_type: AuctionTypeTag, paused: bool) {
+        match auction_type {
+            AuctionTypeTag::Traditional => self.traditional_paused = paused,
+            AuctionTypeTag::Dutch => self.dutch_paused = paused,
+            AuctionTypeTag::Penny => self.penny_paused = paused,
+        }
+    }
+}
+
+impl ProgramAccount for ProgramState {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [77, 209, 137, 229, 149, 67, 167, 230];
+}
+
+/// Parse a decimal version component at compile time
+const fn parse_version_part(part: &str) -> u16 {
+    let bytes = part.as_bytes();
+    let mut value = 0u16;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    value
+}
+
+/// Program semver as `(major, minor, patch)`
+pub const PROGRAM_VERSION: (u16, u16, u16) = (
+    parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+);
+
+/// Deployed configuration written to return data by `GetVersion`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramInfo {
+    /// Program semver major
+    pub major: u16,
+    /// Program semver minor
+    pub minor: u16,
+    /// Program semver patch
+    pub patch: u16,
+    /// Version byte mixed into every PDA seed
+    pub pda_version: u8,
+    /// Schema version of the stored program state
+    pub program_state_version: u8,
+    /// Layout version new auctions are written in
+    pub auction_layout_version: u8,
+    /// Fee rate in basis points
+    pub fee_rate: u64,
+    /// Fee denominator
+    pub fee_denominator: u64,
+    /// Global pause flag
+    pub paused: bool,
+    /// Traditional auctions paused
+    pub traditional_paused: bool,
+    /// Dutch auctions paused
+    pub dutch_paused: bool,
+    /// Penny auctions paused
+    pub penny_paused: bool,
+    /// Curated dealer mode
+    pub curated: bool,
+    /// Auction creation paused
+    pub creation_paused: bool,
+    /// Bids and purchases paused
+    pub bidding_paused: bool,
+}
+
+impl ProgramInfo {
+    /// Snapshot the compiled constants and the stored config flags
+    pub fn new(state: &ProgramState) -> Self {
+        let (major, minor, patch) = PROGRAM_VERSION;
+        Self {
+            major,
+            minor,
+            patch,
+            pda_version: PDA_VERSION,
+            program_state_version: state.schema_version,
+            auction_layout_version: AUCTION_LAYOUT_VERSION,
+            fee_rate: FEE_RATE,
+            fee_denominator: FEE_DENOMINATOR,
+            paused: state.paused,
+            traditional_paused: state.traditional_paused,
+            dutch_paused: state.dutch_paused,
+            penny_paused: state.penny_paused,
+            curated: state.curated,
+            creation_paused: state.creation_paused,
+            bidding_paused: state.bidding_paused,
+        }
+    }
+}
+
+/// Derived auction values written to return data by `GetAuctionInfo`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionInfo {
+    /// Auction ID
+    pub auction_id: [u8; 32],
+    /// Current status
+    pub status: AuctionStatus,
+    /// Auction type
+    pub auction_type_tag: AuctionTypeTag,
+    /// Whether a bid or purchase would be taken now
+    pub accepting_bids: bool,
+    /// Current highest bid (0 before the first bid)
+    pub current_bid: u64,
+    /// Number of bids placed
+    pub bid_count: u64,
+    /// Price a Dutch purchase pays now (0 for other auctions)
+    pub current_price: u64,
+    /// Smallest next Traditional bid, or the increment each Penny bid pays
+    /// (0 for Dutch auctions)
+    pub min_next_bid: u64,
+    /// End of bidding (0 for a Penny auction before its first bid)
+    pub deadline: i64,
+    /// End of the dealer's window to accept a bid below the reserve (0 for
+    /// Dutch and Penny auctions)
+    pub acceptance_deadline: i64,
+    /// Time after which the auction may be swept if it never received a bid
+    pub sweepable_at: i64,
+    /// Unix timestamp the values were derived at
+    pub timestamp: i64,
 }
 
 /// Main auction account
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+///
+/// Fixed `repr(C)` layout: the 8-byte aligned fields follow the one-byte
+/// flags so the struct has no implicit padding. The fields at the `*_OFFSET`
+/// constants keep their positions in every layout version, so RPC `memcmp`
+/// filters built on them never need to change.
+#[derive(Debug, Clone, Copy, PartialEq, CheckedBitPattern, NoUninit, Zeroable)]
+#[repr(C)]
 pub struct Auction {
     /// Unique auction ID (32 bytes)
     pub auction_id: [u8; 32],
     /// Schema version for future upgrades
     pub version: u8,
+    /// Account layout version (`AUCTION_LAYOUT_VERSION` when written)
+    pub layout_version: u8,
     /// PDA bump seed
     pub bump: u8,
     /// Escrow PDA bump
     pub escrow_bump: u8,
     /// Current status
     pub status: AuctionStatus,
-    /// Type tag for quick filtering
+    /// Type tag selecting the view of `params`
     pub auction_type_tag: AuctionTypeTag,
+    /// Number of items (max 255 per auction)
+    pub item_count: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+    /// Set by the owner to block bids and finalization
+    pub frozen: bool,
+    /// Whether the required bond has been posted
+    pub bond_posted: bool,
+    /// Whether bids are recorded in a `BidHistory` PDA
+    pub has_bid_history: bool,
+    /// Whether proceeds are shared through a `PayoutSplits` PDA
+    pub has_payout_splits: bool,
+    /// Marketplace-defined listing category (0 if uncategorized)
+    pub category: u16,
+    /// Whether other depositors contribute items through a `CoListing` PDA
+    pub is_co_listed: bool,
+    /// Whether prices are derived from a Pyth feed, through a `UsdPricing` PDA
+    /// for Traditional auctions or a `DutchOracle` PDA for Dutch ones
+    pub has_oracle_pricing: bool,
 
     /// Auction creator
     pub dealer: Pubkey,
     /// Current highest/winning bidder
     pub current_bidder: Pubkey,
-
     /// SPL token for payment
     pub payment_mint: Pubkey,
+    /// Merkle root of allowed bidders (all zeros if open to everyone)
+    pub allowlist_root: [u8; 32],
+    /// Mint bidders must hold (default pubkey if ungated)
+    pub gate_mint: Pubkey,
+    /// Verified Metaplex collection bidders must hold (default pubkey if ungated)
+    pub gate_collection: Pubkey,
+
     /// Current bid amount
     pub current_bid: u64,
-
-    /// Type-specific parameters
-    pub auction_type: AuctionType,
-
-    /// Number of items (max 255 per auction)
-    pub item_count: u8,
-
     /// Creation timestamp
     pub created_at: i64,
     /// Finalization timestamp (0 if not finalized)
     pub finalized_at: i64,
+    /// Dealer bond required for this auction (0 if none)
+    pub bond_amount: u64,
+    /// Minimum `gate_mint` balance required to bid
+    pub gate_min_balance: u64,
+    /// Number of bids placed (also the index of the next bid receipt)
+    pub bid_count: u64,
 
-    /// Initialized flag
-    pub is_initialized: bool,
+    /// Type-specific parameters, read through `traditional`, `dutch` or `penny`
+    pub params: [u64; AUCTION_PARAMS_WORDS],
+
+    /// URI of the off-chain listing JSON (title, description, images),
+    /// zero-padded; all zeros if unset
+    pub metadata_uri: [u8; MAX_METADATA_URI_LEN],
+
+    /// Key the dealer authorized to deposit items and accept bids on their
+    /// behalf (default pubkey if none)
+    pub delegate: Pubkey,
+
+    /// `hash_reserve` commitment to a hidden reserve price, until the dealer
+    /// reveals it (all zeros if the reserve is public)
+    pub reserve_commitment: [u8; 32],
+
+    /// Key that rules on disputes over the sale (default pubkey if none).
+    /// Arbitrated sales keep the payment in escrow through `DISPUTE_WINDOW`.
+    pub arbiter: Pubkey,
+    /// Whether the winning payment is held in escrow for the arbiter
+    pub proceeds_held: bool,
+    /// Whether a `Dispute` is open, freezing item claims until it is resolved
+    pub disputed: bool,
+    /// Whether the dealer's proceeds vest through a `ProceedsVesting` PDA
+    pub has_proceeds_vesting: bool,
+    /// Whether the dealer's proceeds are swapped through a `SettlementSwap` PDA
+    pub has_settlement_swap: bool,
+    /// Seconds after finalization the payment waits in escrow for the winner
+    /// to confirm delivery (0 pays the dealer at once)
+    pub delivery_timeout: u32,
+
+    /// Wallet receiving `charity_bps` of the proceeds ahead of the dealer
+    /// (default pubkey if none)
+    pub charity: Pubkey,
+    /// Charity's share of the proceeds after the fee, in basis points
+    pub charity_bps: u16,
+    /// Whether bidding waits for the previous lot of `series` to settle
+    pub lot_pending: bool,
+    /// Whether the Dutch schedule prices units of a `ClockSale` rather than
+    /// the whole lot
+    pub is_clock_sale: bool,
+    /// Whether units of the item trade along a `BondingCurve` instead of the
+    /// Dutch schedule
+    pub is_curve_sale: bool,
+    /// Whether the item is held under a `HarbergerListing` instead of sold on
+    /// the Dutch schedule
+    pub is_harberger_listing: bool,
+    /// Whether the Dutch schedule prices units of a `UnitSale` bought
+    /// outright by any number of buyers
+    pub is_unit_sale: bool,
+    /// Whether the owner set this auction's fee rate through a `FeeOverride` PDA
+    pub has_fee_override: bool,
+
+    /// `AuctionSeries` this auction is a lot of (default pubkey if none)
+    pub series: Pubkey,
+
+    /// Seconds after the deadline the dealer has to accept a bid below the
+    /// reserve, from `ProgramState::acceptance_period` at creation; 0 uses
+    /// `DEFAULT_ACCEPTANCE_PERIOD`.
+    pub acceptance_period: i64,
+
+    /// Whether any item carries a royalty, so every settlement must pass the
+    /// items and pay their beneficiaries
+    pub has_item_royalties: bool,
+    /// Whether each item has a vault of its own, keyed by item index rather
+    /// than mint, so a mint can be deposited more than once
+    pub item_vaults_by_index: bool,
+    /// Whether the dealer held a `FeeExemption` at creation, so every fee
+    /// charged on the auction must pass its PDA
+    pub has_fee_exemption: bool,
+    /// Keeps the layout a multiple of 8 bytes
+    pub _reserved: [u8; 5],
 }
 
 impl Auction {
-    /// Conservative max size
-    pub const LEN: usize = 32 + 1 + 1 + 1 + 1 + 1 + 32 + 32 + 32 + 8 + 100 + 1 + 8 + 8 + 1; // ~259 bytes
+    /// Account size (728 bytes)
+    pub const LEN: usize = 48
+        + 6 * 32
+        + 6 * 8
+        + AUCTION_PARAMS_WORDS * 8
+        + MAX_METADATA_URI_LEN
+        + 32
+        + 32
+        + 40
+        + 40
+        + 32
+        + 8
+        + 8;
     /// Account space with discriminator
-    pub const SPACE: usize = 8 + Self::LEN + 50; // buffer for future fields
+    pub const SPACE: usize = 8 + Self::LEN;
+    /// Offset of `layout_version` in account data. Every layout keeps
+    /// `auction_id`, `version` and `layout_version` first so it can be read
+    /// before the rest of the account is decoded.
+    pub const LAYOUT_VERSION_OFFSET: usize = 8 + 32 + 1;
+    /// Offset of `status` in account data, for memcmp filters
+    pub const STATUS_OFFSET: usize = 8 + 36;
+    /// Offset of `auction_type_tag` in account data, for memcmp filters
+    pub const AUCTION_TYPE_TAG_OFFSET: usize = 8 + 37;
+    /// Offset of `category` in account data, for memcmp filters
+    pub const CATEGORY_OFFSET: usize = 8 + 44;
+    /// Offset of `dealer` in account data, for memcmp filters
+    pub const DEALER_OFFSET: usize = 8 + 48;
+    /// Offset of `payment_mint` in account data, for memcmp filters
+    pub const PAYMENT_MINT_OFFSET: usize = 8 + 112;
+
+    /// Pack type-specific parameters into the fixed parameter block
+    pub fn pack_params<T: NoUninit>(params: &T) -> [u64; AUCTION_PARAMS_WORDS] {
+        let mut raw = [0u64; AUCTION_PARAMS_WORDS];
+        let bytes = bytemuck::bytes_of(params);
+        bytemuck::bytes_of_mut(&mut raw)[..bytes.len()].copy_from_slice(bytes);
+        raw
+    }
+
+    fn params<T: CheckedBitPattern>(&self, tag: AuctionTypeTag) -> Result<&T, ProgramError> {
+        if self.auction_type_tag != tag {
+            return Err(AuctionError::InvalidAuctionType.into());
+        }
+        let bytes = &bytemuck::bytes_of(&self.params)[..std::mem::size_of::<T>()];
+        bytemuck::checked::try_from_bytes(bytes).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    fn params_mut<T: CheckedBitPattern + NoUninit>(
+        &mut self,
+        tag: AuctionTypeTag,
+    ) -> Result<&mut T, ProgramError> {
+        if self.auction_type_tag != tag {
+            return Err(AuctionError::InvalidAuctionType.into());
+        }
+        let bytes = &mut bytemuck::bytes_of_mut(&mut self.params)[..std::mem::size_of::<T>()];
+        bytemuck::checked::try_from_bytes_mut(bytes).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Traditional parameters, or `InvalidAuctionType` for other auctions
+    pub fn traditional(&self) -> Result<&TraditionalParams, ProgramError> {
+        self.params(AuctionTypeTag::Traditional)
+    }
+
+    /// Mutable traditional parameters
+    pub fn traditional_mut(&mut self) -> Result<&mut TraditionalParams, ProgramError> {
+        self.params_mut(AuctionTypeTag::Traditional)
+    }
+
+    /// Dutch parameters, or `InvalidAuctionType` for other auctions
+    pub fn dutch(&self) -> Result<&DutchParams, ProgramError> {
+        self.params(AuctionTypeTag::Dutch)
+    }
+
+    /// Mutable Dutch parameters
+    pub fn dutch_mut(&mut self) -> Result<&mut DutchParams, ProgramError> {
+        self.params_mut(AuctionTypeTag::Dutch)
+    }
+
+    /// Penny parameters, or `InvalidAuctionType` for other auctions
+    pub fn penny(&self) -> Result<&PennyParams, ProgramError> {
+        self.params(AuctionTypeTag::Penny)
+    }
+
+    /// Mutable penny parameters
+    pub fn penny_mut(&mut self) -> Result<&mut PennyParams, ProgramError> {
+        self.params_mut(AuctionTypeTag::Penny)
+    }
+
+    /// Time after which the auction may be swept if it never received a bid.
+    /// Penny timers only start with the first bid, so their nominal deadline
+    /// is one timer period after creation.
+    pub fn sweepable_at(&self) -> Result<i64, ProgramError> {
+        let deadline = match self.auction_type_tag {
+            AuctionTypeTag::Traditional => self.traditional()?.deadline,
+            AuctionTypeTag::Dutch => self.dutch()?.deadline,
+            AuctionTypeTag::Penny => self.created_at.saturating_add(self.penny()?.timer_duration),
+        };
+        Ok(deadline.saturating_add(SWEEP_GRACE_PERIOD))
+    }
+
+    /// Time bidding ends: the deadline, or a Penny auction's running timer.
+    /// The first Penny bid starts the timer, so until then bidding would end
+    /// a timer period after `now`.
+    pub fn effective_deadline(&self, now: i64) -> Result<i64, ProgramError> {
+        Ok(match self.auction_type_tag {
+            AuctionTypeTag::Traditional => self.traditional()?.deadline,
+            AuctionTypeTag::Dutch => self.dutch()?.deadline,
+            AuctionTypeTag::Penny => {
+                let params = self.penny()?;
+                if params.current_deadline > 0 {
+                    params.current_deadline
+                } else {
+                    now.saturating_add(params.timer_duration)
+                }
+            }
+        })
+    }
+
+    /// Whether the auction takes bids or purchases at `now`: Active, not
+    /// frozen or waiting on its series, bonded and before its effective
+    /// deadline. Program pauses and bidder gates are checked separately.
+    pub fn is_biddable(&self, now: i64) -> Result<bool, ProgramError> {
+        Ok(self.is_initialized
+            && self.status == AuctionStatus::Active
+            && !self.frozen
+            && !self.lot_pending
+            && self.bond_posted
+            && now <= self.effective_deadline(now)?)
+    }
+
+    /// Seconds of bidding left at `now` (0 once the effective deadline has
+    /// passed)
+    pub fn time_remaining(&self, now: i64) -> Result<i64, ProgramError> {
+        Ok(self.effective_deadline(now)?.saturating_sub(now).max(0))
+    }
+
+    /// Pack a metadata URI into the fixed buffer, rejecting URIs over
+    /// `MAX_METADATA_URI_LEN` bytes
+    pub fn pack_metadata_uri(uri: &str) -> Result<[u8; MAX_METADATA_URI_LEN], ProgramError> {
+        if uri.len() > MAX_METADATA_URI_LEN {
+            return Err(AuctionError::MetadataUriTooLong.into());
+        }
+        let mut raw = [0u8; MAX_METADATA_URI_LEN];
+        raw[..uri.len()].copy_from_slice(uri.as_bytes());
+        Ok(raw)
+    }
+
+    /// Metadata URI with the zero padding trimmed (empty if unset)
+    pub fn metadata_uri(&self) -> &str {
+        let len = self
+            .metadata_uri
+            .iter()
+            .rposition(|&b| b != 0)
+            .map_or(0, |i| i + 1);
+        std::str::from_utf8(&self.metadata_uri[..len]).unwrap_or_default()
+    }
+
+    /// Whether bidding is restricted to an allowlist
+    pub fn has_allowlist(&self) -> bool {
+        self.allowlist_root != [0u8; 32]
+    }
+
+    /// Whether bidding requires holding `gate_mint`
+    pub fn has_token_gate(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    /// Whether bidding requires holding an NFT from `gate_collection`
+    pub fn has_collection_gate(&self) -> bool {
+        self.gate_collection != Pubkey::default()
+    }
+
+    /// Whether `key` may manage the auction: the dealer or their delegate
+    pub fn is_managed_by(&self, key: &Pubkey) -> bool {
+        *key == self.dealer || (self.delegate != Pubkey::default() && *key == self.delegate)
+    }
+
+    /// Wallet that owns the items `depositor` deposits: the dealer for items
+    /// their delegate deposits, otherwise the depositor
+    pub fn owner_of_record(&self, depositor: &Pubkey) -> Pubkey {
+        if self.is_managed_by(depositor) {
+            self.dealer
+        } else {
+            *depositor
+        }
+    }
+
+    /// Whether the reserve price is committed to but not yet revealed
+    pub fn has_hidden_reserve(&self) -> bool {
+        self.reserve_commitment != [0u8; 32]
+    }
+
+    /// Whether disputes over the sale go to an arbiter
+    pub fn has_arbiter(&self) -> bool {
+        self.arbiter != Pubkey::default()
+    }
+
+    /// Whether part of the proceeds goes to `charity`
+    pub fn has_charity(&self) -> bool {
+        self.charity != Pubkey::default()
+    }
+
+    /// Charity's share of `net` proceeds
+    pub fn charity_share(&self, net: u64) -> u64 {
+        (net as u128 * self.charity_bps as u128 / FEE_DENOMINATOR as u128) as u64
+    }
+
+    /// Whether an item royalty of `royalty_bps` fits beside the charity's
+    /// share, both coming out of the same proceeds
+    pub fn royalty_fits(&self, royalty_bps: u16) -> bool {
+        self.charity_bps as u64 + royalty_bps as u64 <= FEE_DENOMINATOR
+    }
+
+    /// Seed following the auction ID in the vault of the item at `index` of
+    /// `mint`: the index, or the mint where items share a vault per mint
+    pub fn item_vault_seed<'a>(&self, index: &'a u8, mint: &'a Pubkey) -> &'a [u8] {
+        if self.item_vaults_by_index {
+            std::slice::from_ref(index)
+        } else {
+            mint.as_ref()
+        }
+    }
+
+    /// Whether the auction is a lot of an `AuctionSeries`
+    pub fn in_series(&self) -> bool {
+        self.series != Pubkey::default()
+    }
+
+    /// Move the auction's schedule `delay` seconds later, so a series lot
+    /// gets the full bidding window once it opens
+    pub fn postpone(&mut self, delay: i64) -> Result<(), ProgramError> {
+        match self.auction_type_tag {
+            AuctionTypeTag::Traditional => {
+                let params = self.traditional_mut()?;
+                params.deadline = params.deadline.saturating_add(delay);
+            }
+            AuctionTypeTag::Dutch => {
+                let params = self.dutch_mut()?;
+                params.start_time = params.start_time.saturating_add(delay);
+                params.deadline = params.deadline.saturating_add(delay);
+            }
+            // Penny timers only start with the first bid
+            AuctionTypeTag::Penny => {}
+        }
+        self.created_at = self.created_at.saturating_add(delay);
+        Ok(())
+    }
+
+    /// End of the dealer's window to accept a bid below the reserve on an
+    /// auction that ended at `deadline`
+    pub fn acceptance_deadline(&self, deadline: i64) -> i64 {
+        let period = if self.acceptance_period > 0 {
+            self.acceptance_period
+        } else {
+            DEFAULT_ACCEPTANCE_PERIOD
+        };
+        deadline.saturating_add(period)
+    }
+
+    /// End of a Traditional auction's acceptance window: stored when it was
+    /// finalized to Expired, or derived from its deadline while it has not
+    /// been finalized yet
+    pub fn acceptance_ends_at(&self) -> Result<i64, ProgramError> {
+        let params = self.traditional()?;
+        Ok(match self.status {
+            AuctionStatus::Expired => params.acceptance_deadline,
+            _ => self.acceptance_deadline(params.deadline),
+        })
+    }
+
+    /// Whether the winning payment stays in escrow after the sale, for the
+    /// arbiter, until the winner confirms delivery or until the dealer swaps
+    /// it
+    pub fn holds_proceeds(&self) -> bool {
+        self.has_arbiter() || self.delivery_timeout > 0 || self.has_settlement_swap
+    }
 }
 
-/// Tracks items deposited into an auction
+impl PodAccount for Auction {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [218, 94, 247, 242, 126, 233, 131, 81];
+}
+
+/// Traditional parameters of an `AuctionV0`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct TraditionalParamsV0 {
+    pub start_amount: u64,
+    pub increment: u64,
+    pub reserve_price: u64,
+    pub deadline: i64,
+    pub acceptance_deadline: i64,
+    pub reserve_met: bool,
+}
+
+/// Dutch parameters of an `AuctionV0`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DutchParamsV0 {
+    pub start_price: u64,
+    pub decrease_amount: u64,
+    pub interval: i64,
+    pub minimum_price: u64,
+    pub deadline: i64,
+    pub start_time: i64,
+}
+
+/// Penny parameters of an `AuctionV0`
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct PennyParamsV0 {
+    pub increment: u64,
+    pub timer_duration: i64,
+    pub current_deadline: i64,
+    pub total_paid: u64,
+    pub last_bid_time: i64,
+}
+
+/// Type-specific parameters of an `AuctionV0`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub enum AuctionTypeV0 {
+    Traditional(TraditionalParamsV0),
+    Dutch(DutchParamsV0),
+    Penny(PennyParamsV0),
+}
+
+/// `Auction` as the first release of the program stored it: borsh-encoded
+/// from the start of the account, with no discriminator or layout version
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AuctionV0 {
+    pub auction_id: [u8; 32],
+    pub version: u8,
+    pub bump: u8,
+    pub escrow_bump: u8,
+    pub status: AuctionStatus,
+    pub auction_type_tag: AuctionTypeTag,
+    pub dealer: Pubkey,
+    pub current_bidder: Pubkey,
+    pub payment_mint: Pubkey,
+    pub current_bid: u64,
+    pub auction_type: AuctionTypeV0,
+    pub item_count: u8,
+    pub created_at: i64,
+    pub finalized_at: i64,
+    pub is_initialized: bool,
+}
+
+impl AuctionV0 {
+    /// Account space the first release allocated for every auction, which
+    /// no later account type shares
+    pub const SPACE: usize = 317;
+
+    /// The same auction in the current layout, with every later field unset
+    pub fn upgrade(self) -> Auction {
+        let params = match self.auction_type {
+            AuctionTypeV0::Traditional(p) => Auction::pack_params(&TraditionalParams {
+                start_amount: p.start_amount,
+                increment: p.increment,
+                reserve_price: p.reserve_price,
+                deadline: p.deadline,
+                acceptance_deadline: p.acceptance_deadline,
+                reserve_met: p.reserve_met,
+                ..TraditionalParams::default()
+            }),
+            AuctionTypeV0::Dutch(p) => Auction::pack_params(&DutchParams {
+                start_price: p.start_price,
+                decrease_amount: p.decrease_amount,
+                interval: p.interval,
+                minimum_price: p.minimum_price,
+                deadline: p.deadline,
+                start_time: p.start_time,
+            }),
+            AuctionTypeV0::Penny(p) => Auction::pack_params(&PennyParams {
+                increment: p.increment,
+                timer_duration: p.timer_duration,
+                current_deadline: p.current_deadline,
+                total_paid: p.total_paid,
+                last_bid_time: p.last_bid_time,
+            }),
+        };
+        Auction {
+            auction_id: self.auction_id,
+            version: self.version,
+            layout_version: AUCTION_LAYOUT_VERSION,
+            bump: self.bump,
+            escrow_bump: self.escrow_bump,
+            status: self.status,
+            auction_type_tag: self.auction_type_tag,
+            item_count: self.item_count,
+            is_initialized: self.is_initialized,
+            dealer: self.dealer,
+            current_bidder: self.current_bidder,
+            payment_mint: self.payment_mint,
+            current_bid: self.current_bid,
+            created_at: self.created_at,
+            finalized_at: self.finalized_at,
+            params,
+            ..Auction::zeroed()
+        }
+    }
+}
+
+/// Smallest bid the program takes next on `auction` at its listed amounts:
+/// the start amount opens a Traditional auction and later bids raise it by
+/// `min_raise`, while every Penny bid pays the fixed increment. Auctions
+/// priced in USD or with an increment table are priced from their PDAs too,
+/// which `GetAuctionInfo` reads. Dutch auctions take no bids.
+pub fn calculate_min_next_bid(auction: &Auction) -> Result<u64, AuctionError> {
+    match auction.auction_type_tag {
+        AuctionTypeTag::Traditional => auction
+            .traditional()
+            .map_err(|_| AuctionError::InvalidAuctionType)?
+            .min_bid(auction.current_bid)
+            .ok_or(AuctionError::MathOverflow),
+        AuctionTypeTag::Penny => auction
+            .penny()
+            .map(|params| params.increment)
+            .map_err(|_| AuctionError::InvalidAuctionType),
+        AuctionTypeTag::Dutch => Err(AuctionError::InvalidAuctionType),
+    }
+}
+
+/// Tracks items deposited into an auction
+#[derive(Debug, Clone, Copy, PartialEq, CheckedBitPattern, NoUninit, Zeroable)]
+#[repr(C)]
 pub struct AuctionItem {
     /// Parent auction ID
     pub auction_id: [u8; 32],
@@ -204,15 +985,102 @@ pub struct AuctionItem {
     pub index: u8,
     /// Initialized flag
     pub is_initialized: bool,
+    /// True if a stack of a semi-fungible token, `amount` counting its units
+    pub is_sft: bool,
+    /// Aligns `depositor` past the original 80-byte layout
+    pub _padding: [u8; 3],
+    /// Owner of record of the item: the wallet that deposited it, or the
+    /// dealer for items their delegate deposited (default for items deposited
+    /// before co-listing, which were all the dealer's)
+    pub depositor: Pubkey,
+    /// Value the depositor declared for the item, weighting their share of
+    /// a co-listed auction's proceeds
+    pub valuation: u64,
+    /// Lowest winning bid at which the item is part of the sale; below it
+    /// the item returns to its depositor (0 if the item always sells)
+    pub reserve_price: u64,
+    /// Wallet paid the item's royalty (default pubkey if none)
+    pub royalty_beneficiary: Pubkey,
+    /// Royalty on the item's share of the proceeds, in basis points
+    pub royalty_bps: u16,
+    /// Keeps the layout a multiple of 8 bytes
+    pub _reserved: [u8; 6],
 }
 
 impl AuctionItem {
     /// Account size
-    pub const LEN: usize = 32 + 32 + 8 + 1 + 1 + 1 + 1; // 76 bytes
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 1 + 1 + 1 + 1 + 3 + 32 + 8 + 8 + 32 + 2 + 6; // 168 bytes
     /// Account space with discriminator
     pub const SPACE: usize = 8 + Self::LEN;
+    /// Account space of items deposited before co-listing
+    pub const LEGACY_SPACE: usize = 8 + 80;
+
+    /// Wallet the item returns to if it goes unsold
+    pub fn depositor_or(&self, dealer: &Pubkey) -> Pubkey {
+        if self.depositor == Pubkey::default() {
+            *dealer
+        } else {
+            self.depositor
+        }
+    }
+
+    /// Whether a winning bid of `amount` meets the item's own reserve
+    pub fn reserve_met(&self, amount: u64) -> bool {
+        amount >= self.reserve_price
+    }
+
+    /// Whether a beneficiary is owed a royalty on the item's sale
+    pub fn has_royalty(&self) -> bool {
+        self.royalty_bps > 0 && self.royalty_beneficiary != Pubkey::default()
+    }
+}
+
+/// Royalties owed on `net` proceeds from selling `items` (after any charity's
+/// share), as beneficiary and amount. Each item's share of the proceeds is weighted by its declared
+/// valuation, or split equally if none was declared.
+pub fn item_royalties(items: &[AuctionItem], net: u64) -> Vec<(Pubkey, u64)> {
+    let total_valuation: u128 = items.iter().map(|item| item.valuation as u128).sum();
+    items
+        .iter()
+        .filter(|item| item.has_royalty())
+        .map(|item| {
+            let share = (net as u128 * item.valuation as u128)
+                .checked_div(total_valuation)
+                .unwrap_or(net as u128 / items.len() as u128);
+            let royalty = share * item.royalty_bps as u128 / FEE_DENOMINATOR as u128;
+            (item.royalty_beneficiary, royalty as u64)
+        })
+        .filter(|(_, royalty)| *royalty > 0)
+        .collect()
+}
+
+impl PodAccount for AuctionItem {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [250, 138, 194, 185, 227, 30, 55, 200];
 }
 
+// Fixed layouts must match their declared sizes exactly; changing `Auction`
+// requires a new `AUCTION_LAYOUT_VERSION` and a migration, and must leave the
+// filterable offsets in place
+const _: () = assert!(std::mem::size_of::<Auction>() == Auction::LEN);
+const _: () =
+    assert!(DISCRIMINATOR_LEN + std::mem::offset_of!(Auction, status) == Auction::STATUS_OFFSET);
+const _: () = assert!(
+    DISCRIMINATOR_LEN + std::mem::offset_of!(Auction, auction_type_tag)
+        == Auction::AUCTION_TYPE_TAG_OFFSET
+);
+const _: () = assert!(
+    DISCRIMINATOR_LEN + std::mem::offset_of!(Auction, category) == Auction::CATEGORY_OFFSET
+);
+const _: () =
+    assert!(DISCRIMINATOR_LEN + std::mem::offset_of!(Auction, dealer) == Auction::DEALER_OFFSET);
+const _: () = assert!(
+    DISCRIMINATOR_LEN + std::mem::offset_of!(Auction, payment_mint) == Auction::PAYMENT_MINT_OFFSET
+);
+const _: () = assert!(std::mem::size_of::<AuctionItem>() == AuctionItem::LEN);
+const _: () = assert!(std::mem::size_of::<TraditionalParams>() <= AUCTION_PARAMS_WORDS * 8);
+const _: () = assert!(std::mem::size_of::<DutchParams>() <= AUCTION_PARAMS_WORDS * 8);
+const _: () = assert!(std::mem::size_of::<PennyParams>() <= AUCTION_PARAMS_WORDS * 8);
+
 /// Fee vault for accumulated fees
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
 pub struct FeeVault {
@@ -233,23 +1101,1515 @@ impl FeeVault {
     pub const SPACE: usize = 8 + Self::LEN;
 }
 
-/// Calculate fee and net amount
-pub fn calculate_fee(amount: u64) -> (u64, u64) {
-    let fee = amount.saturating_mul(FEE_RATE) / FEE_DENOMINATOR;
-    let net = amount.saturating_sub(fee);
-    (fee, net)
+impl ProgramAccount for FeeVault {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [192, 178, 69, 232, 58, 149, 157, 132];
 }
 
-/// Calculate Dutch auction current price
-pub fn calculate_dutch_price(params: &DutchParams, current_time: i64) -> u64 {
-    if current_time <= params.start_time {
-        return params.start_price;
-    }
+/// Marks a wallet as barred from creating auctions or depositing items
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BlacklistEntry {
+    /// Blacklisted wallet
+    pub wallet: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
 
-    let elapsed = current_time.saturating_sub(params.start_time);
-    let intervals = elapsed / params.interval;
-    let total_decrease = (intervals as u64).saturating_mul(params.decrease_amount);
+impl BlacklistEntry {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + 1; // 34 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for BlacklistEntry {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [218, 179, 231, 40, 141, 25, 168, 189];
+}
+
+/// Marks a payment mint whose protocol fees are burned instead of
+/// accumulating in its fee vault
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct FeeBurnEntry {
+    /// Payment mint whose fees are burned
+    pub payment_mint: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl FeeBurnEntry {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + 1; // 34 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for FeeBurnEntry {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [37, 89, 51, 51, 238, 164, 209, 29];
+}
+
+/// Exempts a dealer's sales from the protocol fee
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct FeeExemption {
+    /// Exempt dealer wallet
+    pub dealer: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl FeeExemption {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + 1; // 34 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for FeeExemption {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [203, 171, 87, 15, 227, 37, 162, 31];
+}
+
+/// Fee rate the owner negotiated for one auction, charged in place of the
+/// standard fee and any dealer exemption
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct FeeOverride {
+    /// Auction the rate applies to
+    pub auction_id: [u8; 32],
+    /// Fee rate in basis points
+    pub fee_bps: u16,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl FeeOverride {
+    /// Account size
+    pub const LEN: usize = 32 + 2 + 1 + 1; // 36 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for FeeOverride {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [45, 33, 41, 248, 253, 236, 239, 85];
+}
+
+/// Dealer registration used in curated mode
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DealerProfile {
+    /// Registered dealer wallet
+    pub dealer: Pubkey,
+    /// Whether the owner has approved this dealer
+    pub approved: bool,
+    /// Registration timestamp
+    pub registered_at: i64,
+    /// Timestamp of the last approval change (0 if never reviewed)
+    pub reviewed_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DealerProfile {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + 8 + 8 + 1 + 1; // 51 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for DealerProfile {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [26, 54, 142, 96, 242, 238, 187, 109];
+}
+
+/// Dealer bond requirement for a payment mint
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BondConfig {
+    /// Payment mint this requirement applies to
+    pub payment_mint: Pubkey,
+    /// Bond amount required per auction
+    pub amount: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BondConfig {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 1 + 1; // 42 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for BondConfig {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [142, 20, 216, 1, 7, 142, 247, 125];
+}
+
+/// Dealer bond lifecycle
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum BondStatus {
+    /// Bond held in the bond vault
+    #[default]
+    Posted = 0,
+    /// Bond returned to the dealer after settlement
+    Withdrawn = 1,
+    /// Bond moved to the fee vault by the owner
+    Slashed = 2,
+}
+
+/// Refundable bond posted by a dealer against an auction
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DealerBond {
+    /// Bonded auction ID
+    pub auction_id: [u8; 32],
+    /// Dealer who posted the bond
+    pub dealer: Pubkey,
+    /// Bond token mint (the auction's payment mint)
+    pub payment_mint: Pubkey,
+    /// Bonded amount
+    pub amount: u64,
+    /// Current status
+    pub status: BondStatus,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Bond vault PDA bump
+    pub vault_bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DealerBond {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 1 + 1 + 1 + 1; // 108 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for DealerBond {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [114, 13, 183, 188, 141, 159, 25, 135];
+}
+
+/// Marks a bidder as proven against an auction's allowlist
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct AllowlistPass {
+    /// Auction the pass is valid for
+    pub auction_id: [u8; 32],
+    /// Allowed bidder
+    pub bidder: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl AllowlistPass {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 1 + 1; // 66 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for AllowlistPass {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [84, 190, 211, 149, 46, 2, 85, 129];
+}
+
+/// Commitment to a hidden reserve price, `keccak(reserve_price || salt)`.
+/// The salt keeps small reserves from being found by brute force.
+pub fn hash_reserve(reserve_price: u64, salt: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[&reserve_price.to_le_bytes(), salt]).to_bytes()
+}
+
+/// Verify a Merkle proof that `wallet` is included under `root`
+///
+/// Leaves are `keccak(wallet)` and pairs are hashed in sorted order, so
+/// proofs carry no left/right flags.
+pub fn verify_allowlist_proof(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let mut node = keccak::hash(wallet.as_ref()).to_bytes();
+    for sibling in proof {
+        node = if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        };
+    }
+    node == *root
+}
+
+/// Number of bids kept in a `BidHistory` ring buffer
+pub const BID_HISTORY_LEN: usize = 16;
+
+/// Single bid history entry
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BidRecord {
+    /// Bidder wallet
+    pub bidder: Pubkey,
+    /// Amount committed by this bid
+    pub amount: u64,
+    /// Bid timestamp
+    pub timestamp: i64,
+}
+
+impl BidRecord {
+    /// Serialized size
+    pub const LEN: usize = 32 + 8 + 8; // 48 bytes
+}
+
+/// Ring buffer of the most recent bids on an auction
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidHistory {
+    /// Parent auction ID
+    pub auction_id: [u8; 32],
+    /// Slot the next bid is written to
+    pub head: u8,
+    /// Number of filled slots (saturates at `BID_HISTORY_LEN`)
+    pub len: u8,
+    /// Total bids recorded over the auction's lifetime
+    pub total_bids: u64,
+    /// Ring buffer entries
+    pub entries: [BidRecord; BID_HISTORY_LEN],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidHistory {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + 1 + 8 + BidRecord::LEN * BID_HISTORY_LEN + 1 + 1; // 812 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Append a bid, overwriting the oldest entry once full
+    pub fn push(&mut self, record: BidRecord) {
+        self.entries[self.head as usize] = record;
+        self.head = ((self.head as usize + 1) % BID_HISTORY_LEN) as u8;
+        self.len = (self.len as usize + 1).min(BID_HISTORY_LEN) as u8;
+        self.total_bids = self.total_bids.saturating_add(1);
+    }
+
+    /// Iterate entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &BidRecord> {
+        let start = (self.head as usize + BID_HISTORY_LEN - self.len as usize) % BID_HISTORY_LEN;
+        (0..self.len as usize).map(move |i| &self.entries[(start + i) % BID_HISTORY_LEN])
+    }
+}
+
+impl ProgramAccount for BidHistory {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [199, 193, 173, 10, 170, 179, 35, 237];
+}
+
+/// Proof of a single bid, owned by the bidder's wallet for enumeration
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidReceipt {
+    /// Auction the bid was placed on
+    pub auction_id: [u8; 32],
+    /// Bidder wallet
+    pub bidder: Pubkey,
+    /// Amount committed by this bid
+    pub amount: u64,
+    /// Slot the bid landed in
+    pub slot: u64,
+    /// Bid index within the auction
+    pub bid_index: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidReceipt {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1; // 90 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for BidReceipt {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [186, 150, 141, 135, 59, 122, 39, 99];
+}
+
+/// Outcome of a dispute
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisputeResolution {
+    /// Awaiting the arbiter's ruling
+    #[default]
+    Pending,
+    /// Sale upheld: the dealer is paid and the winner keeps the items
+    Released,
+    /// Sale undone: the winner withdraws their bid and the items go back
+    Refunded,
+    /// `refund_bps` of the payment went back to the winner, who keeps the
+    /// items
+    Split,
+}
+
+/// Dispute over an arbitrated sale, opened by its dealer or winner
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct Dispute {
+    /// Auction under dispute
+    pub auction_id: [u8; 32],
+    /// Party that opened the dispute
+    pub opened_by: Pubkey,
+    /// Unix timestamp the dispute was opened at
+    pub opened_at: i64,
+    /// Arbiter's ruling
+    pub resolution: DisputeResolution,
+    /// Basis points of the payment refunded by a `Split` ruling
+    pub refund_bps: u16,
+    /// Unix timestamp of the ruling (0 while pending)
+    pub resolved_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl Dispute {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 2 + 8 + 1 + 1; // 85 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for Dispute {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [36, 49, 241, 67, 40, 36, 241, 74];
+}
+
+/// Linear release of a Traditional auction's sale proceeds, which stay in the
+/// escrow and are paid out with `ClaimVested`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ProceedsVesting {
+    /// Auction the proceeds are escrowed by
+    pub auction_id: [u8; 32],
+    /// Seconds over which the proceeds vest
+    pub duration: u32,
+    /// When vesting began: the sale's payout (0 until then)
+    pub start: i64,
+    /// Net proceeds vesting, after the fee
+    pub total: u64,
+    /// Amount paid out so far
+    pub claimed: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl ProceedsVesting {
+    /// Account size
+    pub const LEN: usize = 32 + 4 + 8 + 8 + 8 + 1 + 1; // 62 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Amount vested at `now`
+    pub fn vested(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start).max(0) as u64;
+        if elapsed >= self.duration as u64 {
+            return self.total;
+        }
+        (self.total as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+
+    /// Amount vested at `now` and not yet claimed
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.claimed)
+    }
+}
+
+impl ProgramAccount for ProceedsVesting {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [223, 105, 216, 84, 28, 160, 171, 132];
+}
+
+/// Conversion of a Traditional auction's proceeds into another mint. The
+/// payment stays in escrow after the sale until the dealer settles it with
+/// `SwapProceeds`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct SettlementSwap {
+    /// Auction the proceeds are escrowed by
+    pub auction_id: [u8; 32],
+    /// Mint the dealer's proceeds are swapped into
+    pub target_mint: Pubkey,
+    /// Largest shortfall accepted against the quoted output, in basis points
+    pub max_slippage_bps: u16,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl SettlementSwap {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 2 + 1 + 1; // 68 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Lowest output accepted for a swap quoted at `quoted_out`
+    pub fn min_out(&self, quoted_out: u64) -> u64 {
+        let shortfall =
+            quoted_out as u128 * self.max_slippage_bps as u128 / FEE_DENOMINATOR as u128;
+        quoted_out.saturating_sub(shortfall as u64)
+    }
+}
+
+impl ProgramAccount for SettlementSwap {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [150, 244, 181, 10, 41, 104, 200, 208];
+}
+
+/// Ordered lots of one dealer, each opening for bids only once the one
+/// before it has settled
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct AuctionSeries {
+    /// Dealer-chosen series ID
+    pub series_id: [u8; 32],
+    /// Dealer of every lot
+    pub dealer: Pubkey,
+    /// Number of lots in use
+    pub len: u8,
+    /// Auction IDs of the lots, in bidding order
+    pub lots: [[u8; 32]; MAX_SERIES_LOTS],
+    /// Number of lots opened so far; the last of them is the current lot
+    pub opened: u8,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl AuctionSeries {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 1 + MAX_SERIES_LOTS * 32 + 1 + 1 + 1; // 580 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Auction IDs of the lots in use
+    pub fn lots(&self) -> &[[u8; 32]] {
+        &self.lots[..self.len as usize]
+    }
+
+    /// Auction ID of the lot currently open for bids
+    pub fn current_lot(&self) -> Option<&[u8; 32]> {
+        self.lots().get((self.opened as usize).checked_sub(1)?)
+    }
+
+    /// Auction ID of the lot to open next, if any is left
+    pub fn next_lot(&self) -> Option<&[u8; 32]> {
+        self.lots().get(self.opened as usize)
+    }
+}
+
+impl ProgramAccount for AuctionSeries {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [11, 185, 54, 18, 232, 88, 211, 61];
+}
+
+/// Descending-clock sale of a Dutch auction's single item to many buyers.
+/// The Dutch schedule prices one unit; buyers register intents as the price
+/// falls, and all of them pay the price at which demand covered `supply`, or
+/// the floor if it never did.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ClockSale {
+    /// Auction whose item is on sale
+    pub auction_id: [u8; 32],
+    /// Mint of the item
+    pub mint: Pubkey,
+    /// Units on sale
+    pub supply: u64,
+    /// Base units of `mint` in one unit
+    pub unit_amount: u64,
+    /// Units registered so far, never more than `supply`
+    pub demand: u64,
+    /// Price per unit every buyer pays, set once demand covers the supply or
+    /// at clearing
+    pub clearing_price: u64,
+    /// Whether the sale has cleared and intents can settle
+    pub cleared: bool,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl ClockSale {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 1; // 99 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether registered demand covers the supply, stopping the clock
+    pub fn covered(&self) -> bool {
+        self.demand >= self.supply
+    }
+
+    /// Units still open to intents
+    pub fn remaining(&self) -> u64 {
+        self.supply.saturating_sub(self.demand)
+    }
+}
+
+impl ProgramAccount for ClockSale {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [72, 232, 133, 39, 136, 23, 219, 43];
+}
+
+/// Units a buyer registered for in a `ClockSale`, with the payment escrowed
+/// at the clock price when they registered
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ClockIntent {
+    /// Auction the payment is escrowed by
+    pub auction_id: [u8; 32],
+    /// Buyer wallet
+    pub buyer: Pubkey,
+    /// Units registered for
+    pub quantity: u64,
+    /// Price per unit when registered
+    pub price: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl ClockIntent {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1; // 82 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Escrowed payment returned to the buyer once the sale clears at
+    /// `clearing_price`
+    pub fn refund(&self, clearing_price: u64) -> u64 {
+        self.quantity
+            .saturating_mul(self.price.saturating_sub(clearing_price))
+    }
+}
+
+impl ProgramAccount for ClockIntent {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [246, 227, 163, 118, 89, 233, 33, 40];
+}
+
+/// Sale of a Dutch auction's single item in units priced along a curve of
+/// the units already sold: the unit after the first `i` costs
+/// `base_price + slope * i^exponent / CURVE_SLOPE_SCALE`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BondingCurve {
+    /// Auction whose item is on sale
+    pub auction_id: [u8; 32],
+    /// Mint of the item
+    pub mint: Pubkey,
+    /// Units on sale
+    pub supply: u64,
+    /// Base units of `mint` in one unit
+    pub unit_amount: u64,
+    /// Units currently held by buyers
+    pub sold: u64,
+    /// Price of the first unit
+    pub base_price: u64,
+    /// Price growth, scaled by `CURVE_SLOPE_SCALE`
+    pub slope: u64,
+    /// Power of units sold the price grows with (1 for linear)
+    pub exponent: u8,
+    /// Whether buyers can sell units back along the curve until the deadline
+    pub sell_back: bool,
+    /// Payments escrowed to fund sell-backs, paid to the dealer at closing
+    pub reserve: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BondingCurve {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 1 + 1; // 116 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Units still for sale
+    pub fn remaining(&self) -> u64 {
+        self.supply.saturating_sub(self.sold)
+    }
+
+    /// Price of `quantity` units following the first `from` sold, rounded up
+    /// for buys and down for sell-backs so the reserve always covers the
+    /// units outstanding. `None` on overflow.
+    pub fn price_of(&self, from: u64, quantity: u64, round_up: bool) -> Option<u64> {
+        let to = from.checked_add(quantity)?;
+        let growth = (self.slope as u128).checked_mul(
+            power_sum(to, self.exponent)?.checked_sub(power_sum(from, self.exponent)?)?,
+        )?;
+        let growth = if round_up {
+            growth.div_ceil(CURVE_SLOPE_SCALE)
+        } else {
+            growth / CURVE_SLOPE_SCALE
+        };
+        let total = (self.base_price as u128)
+            .checked_mul(quantity as u128)?
+            .checked_add(growth)?;
+        u64::try_from(total).ok()
+    }
+}
+
+impl ProgramAccount for BondingCurve {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [23, 183, 248, 55, 96, 216, 172, 96];
+}
+
+/// Sale of a Dutch auction's single item in units, each priced by the Dutch
+/// schedule at the time it is bought, so a fungible lot can go to many buyers
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct UnitSale {
+    /// Auction whose item is on sale
+    pub auction_id: [u8; 32],
+    /// Mint of the item
+    pub mint: Pubkey,
+    /// Units on sale
+    pub supply: u64,
+    /// Base units of `mint` in one unit
+    pub unit_amount: u64,
+    /// Units bought so far
+    pub sold: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl UnitSale {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1; // 90 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Units still for sale
+    pub fn remaining(&self) -> u64 {
+        self.supply.saturating_sub(self.sold)
+    }
+}
+
+impl ProgramAccount for UnitSale {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [16, 139, 174, 195, 113, 25, 136, 216];
+}
+
+/// Sum of `i^exponent` for `i` in `0..n`, for exponents up to
+/// `MAX_CURVE_EXPONENT`
+fn power_sum(n: u64, exponent: u8) -> Option<u128> {
+    let n = n as u128;
+    let m = n.saturating_sub(1);
+    match exponent {
+        0 => Some(n),
+        1 => Some(n.checked_mul(m)? / 2),
+        2 => Some(m.checked_mul(n)?.checked_mul((2 * n).saturating_sub(1))? / 6),
+        3 => (n.checked_mul(m)? / 2).checked_pow(2),
+        _ => None,
+    }
+}
+
+/// Continuous listing of a Dutch auction's single item until the auction's
+/// deadline. The holder names the price anyone can take the item at, and
+/// pays a tax on that price out of a deposit; a holder whose deposit runs
+/// out forfeits the item back to the dealer at `list_price`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct HarbergerListing {
+    /// Auction whose item is listed
+    pub auction_id: [u8; 32],
+    /// Current holder of the item (the dealer until first bought)
+    pub holder: Pubkey,
+    /// Price the holder assessed the item at
+    pub assessed_price: u64,
+    /// Price the dealer lists the item at while holding it
+    pub list_price: u64,
+    /// Price the holder paid for the item
+    pub last_price: u64,
+    /// Tax per `tax_period` on the assessed price, in basis points
+    pub tax_bps: u16,
+    /// Seconds the tax rate applies to
+    pub tax_period: i64,
+    /// Prepaid tax the holder keeps in escrow
+    pub deposit: u64,
+    /// Tax accrued in escrow, paid to the dealer at closing
+    pub tax_collected: u64,
+    /// Unix timestamp tax was last accrued up to
+    pub last_accrued: i64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl HarbergerListing {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 2 + 8 + 8 + 8 + 8 + 1 + 1; // 124 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Tax owed from `last_accrued` to `until`, rounded up. The dealer owes
+    /// no tax on its own item.
+    pub fn tax_due(&self, dealer: &Pubkey, until: i64) -> u64 {
+        if self.holder == *dealer || self.tax_period <= 0 {
+            return 0;
+        }
+        let elapsed = until.saturating_sub(self.last_accrued).max(0) as u128;
+        let tax = (self.assessed_price as u128 * self.tax_bps as u128)
+            .saturating_mul(elapsed)
+            .div_ceil(FEE_DENOMINATOR as u128 * self.tax_period as u128);
+        u64::try_from(tax).unwrap_or(u64::MAX)
+    }
+
+    /// Move the tax owed up to `until` from the deposit to `tax_collected`.
+    /// A deposit that cannot cover it is taken whole and the item forfeited
+    /// to the dealer; returns whether that happened.
+    pub fn accrue(&mut self, dealer: &Pubkey, until: i64) -> bool {
+        let due = self.tax_due(dealer, until);
+        self.last_accrued = self.last_accrued.max(until);
+        if due <= self.deposit {
+            self.deposit -= due;
+            self.tax_collected = self.tax_collected.saturating_add(due);
+            return false;
+        }
+        self.tax_collected = self.tax_collected.saturating_add(self.deposit);
+        self.deposit = 0;
+        self.holder = *dealer;
+        self.assessed_price = self.list_price;
+        true
+    }
+}
+
+impl ProgramAccount for HarbergerListing {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [157, 58, 59, 51, 246, 154, 42, 30];
+}
+
+/// Standing bid for any NFT of a verified Metaplex collection. The bidder
+/// escrows `price` for each of `quantity` NFTs in the bid's associated token
+/// account, and any dealer auctioning one can accept it at once.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct CollectionBid {
+    /// Collection the bid buys from
+    pub collection: Pubkey,
+    /// Bidder wallet
+    pub bidder: Pubkey,
+    /// Mint the bid pays in
+    pub payment_mint: Pubkey,
+    /// Price offered per NFT
+    pub price: u64,
+    /// NFTs still wanted
+    pub quantity: u64,
+    /// Unix timestamp the bid lapses at (0 if it never does)
+    pub expires_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl CollectionBid {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1; // 122 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Payment escrowed for the NFTs still wanted. `None` on overflow.
+    pub fn escrow_needed(&self) -> Option<u64> {
+        self.price.checked_mul(self.quantity)
+    }
+
+    /// Whether the bid has lapsed at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at > 0 && now > self.expires_at
+    }
+}
+
+impl ProgramAccount for CollectionBid {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [86, 60, 163, 27, 132, 247, 131, 39];
+}
+
+/// Delegate a bidder approves on their token account to pay their signed
+/// bids. Each order carries the delegate's current nonce, which settling it
+/// advances, so no signature settles twice.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidDelegate {
+    /// Bidder whose signed bids the delegate pays
+    pub bidder: Pubkey,
+    /// Nonce the bidder's next signed order must carry
+    pub nonce: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidDelegate {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 1 + 1; // 42 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for BidDelegate {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [239, 43, 58, 59, 240, 192, 201, 108];
+}
+
+/// Payment a bidder holds in a Traditional auction's escrow. Whatever no
+/// longer backs the standing bid is pulled with `WithdrawLosingBid`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidDeposit {
+    /// Auction the deposit is escrowed by
+    pub auction_id: [u8; 32],
+    /// Bidder wallet
+    pub bidder: Pubkey,
+    /// Amount escrowed and not yet withdrawn
+    pub amount: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidDeposit {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 1; // 74 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Amount the bidder can withdraw: all of it, less the standing bid while
+    /// they hold it. A refunded auction's winning bid is withdrawable too; a
+    /// settled one was paid out of the escrow.
+    pub fn withdrawable(&self, auction: &Auction) -> u64 {
+        if auction.current_bidder == self.bidder && auction.status != AuctionStatus::Refunded {
+            self.amount.saturating_sub(auction.current_bid)
+        } else {
+            self.amount
+        }
+    }
+}
+
+impl ProgramAccount for BidDeposit {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [194, 227, 194, 168, 162, 181, 243, 59];
+}
+
+/// Number of auction IDs held by one `DealerIndexPage`
+pub const DEALER_INDEX_PAGE_LEN: usize = 32;
+
+/// Count of a dealer's auctions; the IDs themselves live in `DealerIndexPage`s
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DealerIndex {
+    /// Dealer wallet
+    pub dealer: Pubkey,
+    /// Auctions created by this dealer
+    pub auction_count: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DealerIndex {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 1 + 1; // 42 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Page holding the dealer's auction at `position` in creation order
+    pub fn page_of(position: u64) -> u32 {
+        (position / DEALER_INDEX_PAGE_LEN as u64) as u32
+    }
+
+    /// Page the dealer's next auction is recorded in
+    pub fn next_page(&self) -> u32 {
+        Self::page_of(self.auction_count)
+    }
+}
+
+impl ProgramAccount for DealerIndex {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [155, 249, 95, 238, 41, 245, 153, 15];
+}
+
+/// One page of a dealer's auction IDs, in creation order
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DealerIndexPage {
+    /// Dealer wallet
+    pub dealer: Pubkey,
+    /// Page number
+    pub page: u32,
+    /// Number of filled slots
+    pub len: u8,
+    /// Auction IDs; only the first `len` are set
+    pub auction_ids: [[u8; 32]; DEALER_INDEX_PAGE_LEN],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DealerIndexPage {
+    /// Account size
+    pub const LEN: usize = 32 + 4 + 1 + 32 * DEALER_INDEX_PAGE_LEN + 1 + 1; // 1063 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Auction IDs recorded on this page
+    pub fn auction_ids(&self) -> &[[u8; 32]] {
+        &self.auction_ids[..self.len as usize]
+    }
+
+    /// Append an auction ID, failing once the page is full
+    pub fn push(&mut self, auction_id: [u8; 32]) -> Result<(), ProgramError> {
+        let slot = self
+            .auction_ids
+            .get_mut(self.len as usize)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        *slot = auction_id;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl ProgramAccount for DealerIndexPage {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [59, 121, 243, 146, 203, 163, 254, 50];
+}
+
+/// Number of auction IDs held by one `RegistryPage`
+pub const REGISTRY_PAGE_LEN: usize = 32;
+
+/// One page of the global auction registry
+///
+/// Every auction is registered under its creation index, the value of
+/// `ProgramState::auction_count` when it was created. Auctions created before
+/// the registry existed have no entry, so their slots stay zeroed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct RegistryPage {
+    /// Page number
+    pub page: u32,
+    /// Number of slots up to and including the last one written
+    pub len: u8,
+    /// Auction IDs by slot
+    pub auction_ids: [[u8; 32]; REGISTRY_PAGE_LEN],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl RegistryPage {
+    /// Account size
+    pub const LEN: usize = 4 + 1 + 32 * REGISTRY_PAGE_LEN + 1 + 1; // 1031 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Page and slot holding the auction with creation index `index`
+    pub fn locate(index: u64) -> (u32, usize) {
+        (
+            (index / REGISTRY_PAGE_LEN as u64) as u32,
+            (index % REGISTRY_PAGE_LEN as u64) as usize,
+        )
+    }
+
+    /// Auction ID in `slot`, if one was registered there
+    pub fn auction_id(&self, slot: usize) -> Option<&[u8; 32]> {
+        self.auction_ids[..self.len as usize]
+            .get(slot)
+            .filter(|id| **id != [0u8; 32])
+    }
+}
+
+impl ProgramAccount for RegistryPage {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [190, 151, 207, 163, 226, 253, 16, 250];
+}
+
+/// Cumulative protocol statistics for one payment mint
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct MintStats {
+    /// Payment mint the totals are denominated in
+    pub payment_mint: Pubkey,
+    /// Auctions that ended in a sale
+    pub auctions_settled: u64,
+    /// Auctions that ended without a sale
+    pub auctions_refunded: u64,
+    /// Total paid by winners and penny bidders, fees included
+    pub volume: u64,
+    /// Total protocol fees charged
+    pub fees: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl MintStats {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 1 + 1; // 66 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Add a payment of `amount`, of which `fee` went to the protocol
+    pub fn record_payment(&mut self, amount: u64, fee: u64) {
+        self.volume = self.volume.saturating_add(amount);
+        self.fees = self.fees.saturating_add(fee);
+    }
+}
+
+impl ProgramAccount for MintStats {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [137, 217, 172, 217, 89, 249, 85, 214];
+}
+
+/// Lifetime track record of one dealer, shown to bidders as a trust signal
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DealerStats {
+    /// Dealer the totals belong to
+    pub dealer: Pubkey,
+    /// Auctions created
+    pub auctions_created: u64,
+    /// Auctions that ended in a sale
+    pub auctions_settled: u64,
+    /// Auctions that ended without a sale
+    pub auctions_refunded: u64,
+    /// Total paid for the dealer's auctions, in base units of each auction's
+    /// payment mint
+    pub volume: u64,
+    /// Bonds slashed by the owner
+    pub disputes: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DealerStats {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1; // 74 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for DealerStats {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [148, 234, 54, 22, 139, 157, 140, 10];
+}
+
+/// Short-lived key allowed to place Penny bids for one bidder on one auction,
+/// paying from credits the bidder prepaid into the session's token account
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidSession {
+    /// Auction the session is scoped to
+    pub auction_id: [u8; 32],
+    /// Wallet the session bids for
+    pub bidder: Pubkey,
+    /// Key that signs bids during the session
+    pub session_key: Pubkey,
+    /// Unix timestamp after which the session key is rejected
+    pub expires_at: i64,
+    /// Total credits prepaid into the session
+    pub spend_cap: u64,
+    /// Credits spent on bids so far
+    pub spent: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidSession {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1; // 122 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Reject signers other than an unexpired session key
+    pub fn check_signer(&self, signer: &Pubkey, now: i64) -> ProgramResult {
+        if *signer != self.session_key {
+            return Err(AuctionError::SessionKeyMismatch.into());
+        }
+        if now > self.expires_at {
+            return Err(AuctionError::SessionExpired.into());
+        }
+        Ok(())
+    }
+
+    /// Charge `amount` against the spend cap
+    pub fn spend(&mut self, amount: u64) -> ProgramResult {
+        let spent = self
+            .spent
+            .checked_add(amount)
+            .ok_or(AuctionError::MathOverflow)?;
+        if spent > self.spend_cap {
+            return Err(AuctionError::SessionSpendCapExceeded.into());
+        }
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+impl ProgramAccount for BidSession {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [146, 57, 12, 64, 7, 225, 109, 19];
+}
+
+/// Opt-in activity totals for one bidder, for loyalty programs and leaderboards
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct BidderStats {
+    /// Bidder the totals belong to
+    pub bidder: Pubkey,
+    /// Bids placed, Dutch purchases included
+    pub bids_placed: u64,
+    /// Auctions won
+    pub auctions_won: u64,
+    /// Total paid for won auctions and penny bids, in base units of each
+    /// auction's payment mint
+    pub volume: u64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl BidderStats {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1 + 1; // 58 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+}
+
+impl ProgramAccount for BidderStats {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [18, 76, 46, 77, 222, 235, 189, 53];
+}
+
+/// Share of an auction's proceeds paid to someone other than the dealer
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PayoutSplit {
+    /// Wallet paid through its associated token account for the payment mint
+    pub recipient: Pubkey,
+    /// Share of the dealer's proceeds after fees (and after any co-depositors
+    /// are paid), in basis points
+    pub bps: u16,
+}
+
+impl PayoutSplit {
+    /// This split's share of `net`, rounded down
+    pub fn share_of(&self, net: u64) -> u64 {
+        (net as u128 * self.bps as u128 / FEE_DENOMINATOR as u128) as u64
+    }
+}
+
+/// Payout splits of one auction, fixed at creation
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct PayoutSplits {
+    /// Auction the splits apply to
+    pub auction_id: [u8; 32],
+    /// Number of splits in use
+    pub len: u8,
+    /// Splits, filled from the front
+    pub splits: [PayoutSplit; MAX_PAYOUT_SPLITS],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl PayoutSplits {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + MAX_PAYOUT_SPLITS * (32 + 2) + 1 + 1; // 171 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Validate `splits` for a new auction: at most `MAX_PAYOUT_SPLITS`
+    /// non-zero shares to real wallets, together at most 100%
+    pub fn new(
+        auction_id: [u8; 32],
+        splits: &[PayoutSplit],
+        bump: u8,
+    ) -> Result<Self, ProgramError> {
+        let total_bps: u64 = splits.iter().map(|split| split.bps as u64).sum();
+        if splits.len() > MAX_PAYOUT_SPLITS
+            || total_bps > FEE_DENOMINATOR
+            || splits
+                .iter()
+                .any(|split| split.bps == 0 || split.recipient == Pubkey::default())
+        {
+            return Err(AuctionError::InvalidPayoutSplits.into());
+        }
+
+        let mut payout_splits = Self {
+            auction_id,
+            len: splits.len() as u8,
+            bump,
+            is_initialized: true,
+            ..Self::default()
+        };
+        payout_splits.splits[..splits.len()].copy_from_slice(splits);
+        Ok(payout_splits)
+    }
+
+    /// Splits in use
+    pub fn splits(&self) -> &[PayoutSplit] {
+        &self.splits[..self.len as usize]
+    }
+}
+
+impl ProgramAccount for PayoutSplits {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [197, 152, 175, 134, 28, 100, 130, 159];
+}
+
+/// Increment required once the current bid reaches `threshold`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IncrementTier {
+    /// Current bid from which the tier applies
+    pub threshold: u64,
+    /// Minimum raise within the tier
+    pub increment: u64,
+}
+
+/// Auction-house style increments growing with the current bid
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct IncrementTable {
+    /// Auction the table applies to
+    pub auction_id: [u8; 32],
+    /// Number of tiers in use
+    pub len: u8,
+    /// Tiers by ascending threshold, filled from the front
+    pub tiers: [IncrementTier; MAX_INCREMENT_TIERS],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl IncrementTable {
+    /// Account size
+    pub const LEN: usize = 32 + 1 + MAX_INCREMENT_TIERS * (8 + 8) + 1 + 1; // 163 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Validate `tiers`: at most `MAX_INCREMENT_TIERS` non-zero increments
+    /// under strictly ascending thresholds
+    pub fn new(
+        auction_id: [u8; 32],
+        tiers: &[IncrementTier],
+        bump: u8,
+    ) -> Result<Self, ProgramError> {
+        if tiers.len() > MAX_INCREMENT_TIERS
+            || tiers.iter().any(|tier| tier.increment == 0)
+            || tiers
+                .windows(2)
+                .any(|pair| pair[0].threshold >= pair[1].threshold)
+        {
+            return Err(AuctionError::InvalidIncrementTable.into());
+        }
+
+        let mut table = Self {
+            auction_id,
+            len: tiers.len() as u8,
+            bump,
+            is_initialized: true,
+            ..Self::default()
+        };
+        table.tiers[..tiers.len()].copy_from_slice(tiers);
+        Ok(table)
+    }
+
+    /// Tiers in use
+    pub fn tiers(&self) -> &[IncrementTier] {
+        &self.tiers[..self.len as usize]
+    }
+
+    /// Increment of the highest tier `current_bid` has reached (0 below the
+    /// first)
+    pub fn increment_at(&self, current_bid: u64) -> u64 {
+        self.tiers()
+            .iter()
+            .rev()
+            .find(|tier| current_bid >= tier.threshold)
+            .map_or(0, |tier| tier.increment)
+    }
+}
+
+impl ProgramAccount for IncrementTable {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [119, 8, 146, 120, 211, 7, 97, 174];
+}
+
+/// Depositor approved to contribute items to a co-listed auction
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CoDepositor {
+    /// Wallet paid through its associated token account for the payment mint
+    pub depositor: Pubkey,
+    /// Total valuation of the items they deposited
+    pub valuation: u64,
+}
+
+/// Depositors of a co-listed auction and the valuations their share of the
+/// proceeds is weighted by
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct CoListing {
+    /// Auction the depositors contribute to
+    pub auction_id: [u8; 32],
+    /// Total valuation of every item in the auction, the dealer's included
+    pub total_valuation: u64,
+    /// Number of approved depositors
+    pub len: u8,
+    /// Approved depositors, filled from the front
+    pub depositors: [CoDepositor; MAX_CO_DEPOSITORS],
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl CoListing {
+    /// Account size
+    pub const LEN: usize = 32 + 8 + 1 + MAX_CO_DEPOSITORS * (32 + 8) + 1 + 1; // 203 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Approved depositors
+    pub fn depositors(&self) -> &[CoDepositor] {
+        &self.depositors[..self.len as usize]
+    }
+
+    /// Approve `depositor`; approving one twice is a no-op
+    pub fn approve(&mut self, depositor: &Pubkey) -> ProgramResult {
+        if self
+            .depositors()
+            .iter()
+            .any(|entry| entry.depositor == *depositor)
+        {
+            return Ok(());
+        }
+        if self.len as usize == MAX_CO_DEPOSITORS {
+            return Err(AuctionError::TooManyDepositors.into());
+        }
+        self.depositors[self.len as usize] = CoDepositor {
+            depositor: *depositor,
+            valuation: 0,
+        };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Add an item worth `valuation` deposited by `depositor`, who must be the
+    /// dealer or approved
+    pub fn record_deposit(
+        &mut self,
+        depositor: &Pubkey,
+        dealer: &Pubkey,
+        valuation: u64,
+    ) -> ProgramResult {
+        if depositor != dealer {
+            let len = self.len as usize;
+            let entry = self.depositors[..len]
+                .iter_mut()
+                .find(|entry| entry.depositor == *depositor)
+                .ok_or(AuctionError::DepositorNotApproved)?;
+            entry.valuation = entry
+                .valuation
+                .checked_add(valuation)
+                .ok_or(AuctionError::MathOverflow)?;
+        }
+        self.total_valuation = self
+            .total_valuation
+            .checked_add(valuation)
+            .ok_or(AuctionError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Pro-rata share of `net` for items worth `valuation`, rounded down
+    pub fn share_of(&self, valuation: u64, net: u64) -> u64 {
+        if self.total_valuation == 0 {
+            return 0;
+        }
+        (net as u128 * valuation as u128 / self.total_valuation as u128) as u64
+    }
+}
+
+impl ProgramAccount for CoListing {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [133, 32, 3, 130, 232, 131, 186, 67];
+}
+
+/// USD-denominated start and reserve of a Traditional auction, converted to
+/// the payment mint with a Pyth price when bids are placed and when the
+/// auction is finalized
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct UsdPricing {
+    /// Auction the prices apply to
+    pub auction_id: [u8; 32],
+    /// Pyth price account quoting the payment mint in USD
+    pub price_feed: Pubkey,
+    /// Reserve price in USD cents (0 keeps the auction's `reserve_price`)
+    pub reserve_usd_cents: u64,
+    /// Minimum first bid in USD cents (0 keeps the auction's `start_amount`)
+    pub start_usd_cents: u64,
+    /// Decimals of the payment mint
+    pub decimals: u8,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl UsdPricing {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1 + 1; // 83 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Minimum first bid at `price`, or `start_amount` if not set in USD
+    pub fn start_amount(&self, price: &PriceFeed, start_amount: u64) -> Result<u64, ProgramError> {
+        if self.start_usd_cents == 0 {
+            return Ok(start_amount);
+        }
+        price.usd_cents_to_amount(self.start_usd_cents, self.decimals)
+    }
+
+    /// Reserve price at `price`, or `reserve_price` if not set in USD
+    pub fn reserve_price(
+        &self,
+        price: &PriceFeed,
+        reserve_price: u64,
+    ) -> Result<u64, ProgramError> {
+        if self.reserve_usd_cents == 0 {
+            return Ok(reserve_price);
+        }
+        price.usd_cents_to_amount(self.reserve_usd_cents, self.decimals)
+    }
+}
+
+impl ProgramAccount for UsdPricing {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [99, 221, 182, 181, 178, 79, 142, 243];
+}
+
+/// Dutch start price derived from the market: the lot's value at a Pyth
+/// price, times a premium, evaluated whenever a purchase is attempted
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct DutchOracle {
+    /// Auction the start price applies to
+    pub auction_id: [u8; 32],
+    /// Pyth price account quoting the lot's token in the payment currency
+    pub price_feed: Pubkey,
+    /// Multiplier on the market value in basis points (10_000 = at market)
+    pub premium_bps: u16,
+    /// Size of the lot in base units of its token
+    pub lot_amount: u64,
+    /// Decimals of the lot's token
+    pub lot_decimals: u8,
+    /// Decimals of the payment mint
+    pub payment_decimals: u8,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Initialized flag
+    pub is_initialized: bool,
+}
+
+impl DutchOracle {
+    /// Account size
+    pub const LEN: usize = 32 + 32 + 2 + 8 + 1 + 1 + 1 + 1; // 78 bytes
+    /// Account space with discriminator
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Start price at `price`, rounded down
+    pub fn start_price(&self, price: &PriceFeed) -> Result<u64, ProgramError> {
+        let value = price.value_of(self.lot_amount, self.lot_decimals, self.payment_decimals)?;
+        let start_price = value as u128 * self.premium_bps as u128 / FEE_DENOMINATOR as u128;
+        u64::try_from(start_price).map_err(|_| AuctionError::MathOverflow.into())
+    }
+}
 
-    let current_price = params.start_price.saturating_sub(total_decrease);
-    current_price.max(params.minimum_price)
+impl ProgramAccount for DutchOracle {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [154, 205, 162, 161, 177, 208, 16, 128];
 }
@@ -0,0 +1,49 @@
+//! Jupiter CPI for converting sale proceeds
+//!
+//! Routes are quoted and built off-chain against the escrow as the swapping
+//! wallet. The program only forwards the route instruction, signing for the
+//! escrow, so the Jupiter SDK is not pulled in as a dependency.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use crate::error::AuctionError;
+
+/// Jupiter aggregator v6 program ID
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// Invoke the Jupiter route `data` over `route_accounts`, signing for
+/// `authority` with `signer_seeds`
+pub fn invoke_route<'a>(
+    jupiter_program: &AccountInfo<'a>,
+    route_accounts: &[AccountInfo<'a>],
+    authority: &Pubkey,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if *jupiter_program.key != JUPITER_PROGRAM_ID {
+        return Err(AuctionError::InvalidSwapProgram.into());
+    }
+
+    let route = Instruction {
+        program_id: JUPITER_PROGRAM_ID,
+        accounts: route_accounts
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer || info.key == authority,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut infos = route_accounts.to_vec();
+    infos.push(jupiter_program.clone());
+    invoke_signed(&route, &infos, signer_seeds)
+}
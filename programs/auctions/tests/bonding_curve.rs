@@ -0,0 +1,207 @@
+//! Bonding curve: buys escrow their cost as a reserve that funds sell-backs
+//! along the same curve
+
+mod common;
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, GateAccounts},
+    pda::{
+        find_bonding_curve_address, find_escrow_address, find_fee_vault_token_address,
+        find_item_vault_address,
+    },
+    state::{BondingCurve, CURVE_SLOPE_SCALE, MAX_CURVE_EXPONENT},
+};
+use common::*;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_bonding_curve_buy_sell_round_trip() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let auction_id = [1; 32];
+    let (auction, item_mint) = create_dutch_auction(
+        &mut context,
+        &payment_mint,
+        auction_id,
+        10_000,
+        1_000,
+        5_000,
+        100,
+    )
+    .await;
+    let dealer = context.payer.pubkey();
+    // 10 units of 10 base units, from 1,000 rising 100 a unit sold
+    let set = instruction::set_bonding_curve(
+        &auctions::id(),
+        &dealer,
+        &auction_id,
+        10,
+        1_000,
+        100 * CURVE_SLOPE_SCALE as u64,
+        1,
+        true,
+    );
+    send(&mut context, &[set], &[]).await.unwrap();
+
+    let (curve_address, _) = find_bonding_curve_address(&auctions::id(), &auction_id);
+    let (escrow, _) = find_escrow_address(&auctions::id(), &auction_id);
+    let (item_vault, _) = find_item_vault_address(&auctions::id(), &auction, 0, &item_mint);
+    let fee_vault_token = find_fee_vault_token_address(&auctions::id(), &payment_mint);
+    let dealer_token =
+        spl_associated_token_account::get_associated_token_address(&dealer, &payment_mint);
+
+    let buyer = create_wallet(&mut context, 1_000_000_000).await;
+    let buyer_token =
+        create_token_account(&mut context, &payment_mint, &buyer.pubkey(), 10_000).await;
+    let buyer_item =
+        spl_associated_token_account::get_associated_token_address(&buyer.pubkey(), &item_mint);
+
+    // 4 units cost 1,000 + 1,100 + 1,200 + 1,300, escrowed in full
+    let buy = instruction::buy_curve(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        &dealer_token,
+        &item_mint,
+        4,
+        4_600,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[buy], &[&buyer]).await.unwrap();
+
+    let curve: BondingCurve = load_account(&mut context, &curve_address).await.unwrap();
+    assert_eq!((curve.sold, curve.reserve), (4, 4_600));
+    assert_eq!(token_balance(&mut context, &buyer_token).await, 5_400);
+    assert_eq!(token_balance(&mut context, &escrow).await, 4_600);
+    assert_eq!(token_balance(&mut context, &buyer_item).await, 40);
+    assert_eq!(token_balance(&mut context, &item_vault).await, 60);
+
+    // Selling 2 back refunds the last two prices, 2,500, less the 0.5% fee
+    let sell = instruction::sell_curve(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        &item_mint,
+        2,
+        2_488,
+    );
+    send(&mut context, &[sell], &[&buyer]).await.unwrap();
+
+    let curve: BondingCurve = load_account(&mut context, &curve_address).await.unwrap();
+    assert_eq!((curve.sold, curve.reserve), (2, 2_100));
+    assert_eq!(token_balance(&mut context, &buyer_token).await, 7_888);
+    assert_eq!(token_balance(&mut context, &fee_vault_token).await, 12);
+    assert_eq!(token_balance(&mut context, &escrow).await, 2_100);
+    assert_eq!(token_balance(&mut context, &buyer_item).await, 20);
+    assert_eq!(token_balance(&mut context, &item_vault).await, 80);
+
+    // Rebuying them costs what they were sold back for, before the fee
+    let buy = instruction::buy_curve(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        &dealer_token,
+        &item_mint,
+        2,
+        2_500,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[buy], &[&buyer]).await.unwrap();
+
+    let curve: BondingCurve = load_account(&mut context, &curve_address).await.unwrap();
+    assert_eq!((curve.sold, curve.reserve), (4, 4_600));
+    assert_eq!(token_balance(&mut context, &buyer_token).await, 5_388);
+    assert_eq!(token_balance(&mut context, &buyer_item).await, 40);
+
+    // Only units the curve has sold can come back
+    let sell = instruction::sell_curve(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        &item_mint,
+        5,
+        0,
+    );
+    let err = send(&mut context, &[sell], &[&buyer]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::InvalidInstructionData));
+
+    let curve: BondingCurve = load_account(&mut context, &curve_address).await.unwrap();
+    assert_eq!((curve.sold, curve.reserve), (4, 4_600));
+    assert_eq!(token_balance(&mut context, &escrow).await, 4_600);
+}
+
+#[tokio::test]
+async fn test_bonding_curve_overflow_at_max_exponent() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let auction_id = [2; 32];
+    let (auction, item_mint) = create_dutch_auction(
+        &mut context,
+        &payment_mint,
+        auction_id,
+        10_000,
+        1_000,
+        5_000,
+        1_000_000,
+    )
+    .await;
+    let dealer = context.payer.pubkey();
+
+    // Curves steeper than a cube are rejected
+    let set = instruction::set_bonding_curve(
+        &auctions::id(),
+        &dealer,
+        &auction_id,
+        1_000_000,
+        1,
+        u64::MAX,
+        MAX_CURVE_EXPONENT + 1,
+        false,
+    );
+    let err = send(&mut context, &[set], &[]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::InvalidBondingCurve));
+
+    let set = instruction::set_bonding_curve(
+        &auctions::id(),
+        &dealer,
+        &auction_id,
+        1_000_000,
+        1,
+        u64::MAX,
+        MAX_CURVE_EXPONENT,
+        false,
+    );
+    send(&mut context, &[set], &[]).await.unwrap();
+
+    // The cost of 1,000 units on a cubic curve this steep overflows a u64
+    let buyer = create_wallet(&mut context, 1_000_000_000).await;
+    let buyer_token =
+        create_token_account(&mut context, &payment_mint, &buyer.pubkey(), u64::MAX).await;
+    let dealer_token =
+        spl_associated_token_account::get_associated_token_address(&dealer, &payment_mint);
+    let buy = instruction::buy_curve(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        &dealer_token,
+        &item_mint,
+        1_000,
+        u64::MAX,
+        &GateAccounts::default(),
+    );
+    let err = send(&mut context, &[buy], &[&buyer]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::MathOverflow));
+
+    let (curve_address, _) = find_bonding_curve_address(&auctions::id(), &auction_id);
+    let curve: BondingCurve = load_account(&mut context, &curve_address).await.unwrap();
+    assert_eq!((curve.sold, curve.reserve), (0, 0));
+    assert_eq!(token_balance(&mut context, &buyer_token).await, u64::MAX);
+}
@@ -0,0 +1,233 @@
+//! Clock sale: intents escrow at the clock price, the sale clears once they
+//! cover the supply, and each intent settles at the clearing price
+
+mod common;
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, GateAccounts},
+    pda::{
+        find_clock_intent_address, find_clock_sale_address, find_escrow_address,
+        find_fee_vault_token_address, find_item_vault_address,
+    },
+    state::{AuctionStatus, ClockIntent, ClockSale},
+};
+use common::*;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_clock_sale_clears_and_settles() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let auction_id = [1; 32];
+    // 3 units of 10 base units each, the clock dropping 1,000 a minute
+    let (auction, item_mint) = create_dutch_auction(
+        &mut context,
+        &payment_mint,
+        auction_id,
+        10_000,
+        1_000,
+        5_000,
+        30,
+    )
+    .await;
+    let dealer = context.payer.pubkey();
+    let set = instruction::set_clock_sale(&auctions::id(), &dealer, &auction_id, 3);
+    send(&mut context, &[set], &[]).await.unwrap();
+
+    let (clock_sale, _) = find_clock_sale_address(&auctions::id(), &auction_id);
+    let (escrow, _) = find_escrow_address(&auctions::id(), &auction_id);
+    let sale: ClockSale = load_account(&mut context, &clock_sale).await.unwrap();
+    assert_eq!((sale.supply, sale.unit_amount, sale.demand), (3, 10, 0));
+
+    // Alice takes 2 units at the opening price
+    let alice = create_wallet(&mut context, 1_000_000_000).await;
+    let alice_token =
+        create_token_account(&mut context, &payment_mint, &alice.pubkey(), 20_000).await;
+    let register = instruction::register_intent(
+        &auctions::id(),
+        &auction,
+        &alice.pubkey(),
+        &alice_token,
+        2,
+        10_000,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[register], &[&alice]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &alice_token).await, 0);
+    assert_eq!(token_balance(&mut context, &escrow).await, 20_000);
+    let (alice_intent, _) =
+        find_clock_intent_address(&auctions::id(), &auction_id, &alice.pubkey());
+    let intent: ClockIntent = load_account(&mut context, &alice_intent).await.unwrap();
+    assert_eq!((intent.quantity, intent.price), (2, 10_000));
+    let sale: ClockSale = load_account(&mut context, &clock_sale).await.unwrap();
+    assert_eq!(sale.demand, 2);
+    assert!(!sale.covered());
+
+    // Nothing settles while the clock runs
+    let settle = instruction::settle_intent(
+        &auctions::id(),
+        &auction,
+        &dealer,
+        &alice.pubkey(),
+        &item_mint,
+    );
+    let err = send(&mut context, std::slice::from_ref(&settle), &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::ClockSaleNotCleared));
+
+    // Bob asks for 2 units two minutes later, is trimmed to the last one and
+    // stops the clock at 8,000
+    let start = now(&mut context).await;
+    set_time(&mut context, start + 120).await;
+    let bob = create_wallet(&mut context, 1_000_000_000).await;
+    let bob_token = create_token_account(&mut context, &payment_mint, &bob.pubkey(), 20_000).await;
+    let register = instruction::register_intent(
+        &auctions::id(),
+        &auction,
+        &bob.pubkey(),
+        &bob_token,
+        2,
+        8_000,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[register], &[&bob]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &bob_token).await, 12_000);
+    assert_eq!(token_balance(&mut context, &escrow).await, 28_000);
+    let sale: ClockSale = load_account(&mut context, &clock_sale).await.unwrap();
+    assert_eq!((sale.demand, sale.clearing_price), (3, 8_000));
+    assert!(sale.covered() && !sale.cleared);
+
+    // Clearing pays the dealer for 3 units at 8,000, less the 0.5% fee
+    let dealer_token =
+        spl_associated_token_account::get_associated_token_address(&dealer, &payment_mint);
+    let fee_vault_token = find_fee_vault_token_address(&auctions::id(), &payment_mint);
+    let auction = load_auction(&mut context, &auction_id).await;
+    let clear = instruction::clear_clock_sale(&auctions::id(), &auction, &dealer, &dealer_token);
+    send(&mut context, &[clear], &[]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &dealer_token).await, 23_880);
+    assert_eq!(token_balance(&mut context, &fee_vault_token).await, 120);
+    assert_eq!(token_balance(&mut context, &escrow).await, 4_000);
+    let sale: ClockSale = load_account(&mut context, &clock_sale).await.unwrap();
+    assert!(sale.cleared);
+    let auction = load_auction(&mut context, &auction_id).await;
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_bid, 24_000);
+
+    // Alice gets her units and the 2,000 a unit she paid over the clearing
+    // price; her intent closes
+    let (item_vault, _) = find_item_vault_address(&auctions::id(), &auction, 0, &item_mint);
+    let alice_item =
+        spl_associated_token_account::get_associated_token_address(&alice.pubkey(), &item_mint);
+    send(&mut context, std::slice::from_ref(&settle), &[])
+        .await
+        .unwrap();
+
+    assert_eq!(token_balance(&mut context, &alice_item).await, 20);
+    assert_eq!(token_balance(&mut context, &alice_token).await, 4_000);
+    assert_eq!(token_balance(&mut context, &item_vault).await, 10);
+    assert_eq!(token_balance(&mut context, &escrow).await, 0);
+    assert!(context
+        .banks_client
+        .get_account(alice_intent)
+        .await
+        .unwrap()
+        .is_none());
+
+    // A settled intent cannot settle again
+    let err = send(&mut context, &[settle], &[]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::InvalidAccountOwner));
+    assert_eq!(token_balance(&mut context, &alice_item).await, 20);
+
+    // Bob paid the clearing price, so gets no refund
+    let settle = instruction::settle_intent(
+        &auctions::id(),
+        &auction,
+        &dealer,
+        &bob.pubkey(),
+        &item_mint,
+    );
+    send(&mut context, &[settle], &[]).await.unwrap();
+
+    let bob_item =
+        spl_associated_token_account::get_associated_token_address(&bob.pubkey(), &item_mint);
+    assert_eq!(token_balance(&mut context, &bob_item).await, 10);
+    assert_eq!(token_balance(&mut context, &bob_token).await, 12_000);
+    assert_eq!(token_balance(&mut context, &item_vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_clock_sale_clears_only_when_covered_or_expired() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let auction_id = [2; 32];
+    let (auction, _) = create_dutch_auction(
+        &mut context,
+        &payment_mint,
+        auction_id,
+        10_000,
+        1_000,
+        5_000,
+        30,
+    )
+    .await;
+    let dealer = context.payer.pubkey();
+    let set = instruction::set_clock_sale(&auctions::id(), &dealer, &auction_id, 3);
+    send(&mut context, &[set], &[]).await.unwrap();
+
+    let buyer = create_wallet(&mut context, 1_000_000_000).await;
+    let buyer_token =
+        create_token_account(&mut context, &payment_mint, &buyer.pubkey(), 10_000).await;
+    let register = instruction::register_intent(
+        &auctions::id(),
+        &auction,
+        &buyer.pubkey(),
+        &buyer_token,
+        1,
+        10_000,
+        &GateAccounts::default(),
+    );
+    send(&mut context, std::slice::from_ref(&register), &[&buyer])
+        .await
+        .unwrap();
+
+    // One intent per buyer
+    let err = send(&mut context, &[register], &[&buyer])
+        .await
+        .unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::AccountAlreadyInitialized));
+
+    // 1 of 3 units registered before the deadline
+    let dealer_token =
+        spl_associated_token_account::get_associated_token_address(&dealer, &payment_mint);
+    let clear = instruction::clear_clock_sale(&auctions::id(), &auction, &dealer, &dealer_token);
+    let err = send(&mut context, std::slice::from_ref(&clear), &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::AuctionNotExpired));
+
+    // After the deadline the registered unit sells at the floor
+    let dutch = *auction.dutch().unwrap();
+    set_time(&mut context, dutch.deadline + 1).await;
+    send(&mut context, &[clear], &[]).await.unwrap();
+
+    let sale: ClockSale = load_account(
+        &mut context,
+        &find_clock_sale_address(&auctions::id(), &auction_id).0,
+    )
+    .await
+    .unwrap();
+    assert!(sale.cleared);
+    assert_eq!((sale.demand, sale.clearing_price), (1, 5_000));
+    assert_eq!(token_balance(&mut context, &dealer_token).await, 4_975);
+
+    let auction = load_auction(&mut context, &auction_id).await;
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_bid, 5_000);
+}
@@ -0,0 +1,152 @@
+//! Collection bids: a dealer sells an auction's NFT straight to a standing
+//! offer on the collection it is a verified member of
+
+mod common;
+
+use auctions::{
+    error::AuctionError,
+    instruction,
+    metadata::{find_metadata_address, METADATA_PROGRAM_ID},
+    pda::{find_collection_bid_address, find_fee_vault_token_address},
+    state::{AuctionStatus, CollectionBid},
+};
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{account::Account, signature::Signer};
+
+/// Install Metaplex metadata for `mint` naming it a verified member of
+/// `collection`
+fn set_metadata(context: &mut ProgramTestContext, mint: &Pubkey, collection: &Pubkey) {
+    let mut data = vec![4];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    // Empty name, symbol and URI
+    data.extend_from_slice(&[0; 12]);
+    // No royalty or creators, primary sale not happened, mutable, no edition
+    // nonce, the NonFungible token standard
+    data.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+    data.extend_from_slice(&[1, 1]);
+    data.extend_from_slice(collection.as_ref());
+
+    let (address, _) = find_metadata_address(mint);
+    context.set_account(
+        &address,
+        &Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: METADATA_PROGRAM_ID,
+            ..Account::default()
+        }
+        .into(),
+    );
+}
+
+/// Auction `auction_id` by the context payer holding a fresh NFT that is a
+/// member of `collection`
+async fn list_nft(
+    context: &mut ProgramTestContext,
+    payment_mint: &Pubkey,
+    auction_id: [u8; 32],
+    collection: &Pubkey,
+) -> Pubkey {
+    let auction = create_traditional_auction(context, payment_mint, auction_id, 1_000).await;
+    let dealer = context.payer.pubkey();
+    let nft_mint = create_mint(context).await;
+    let dealer_nft = create_token_account(context, &nft_mint, &dealer, 1).await;
+    set_metadata(context, &nft_mint, collection);
+    let deposit = instruction::deposit_nft(
+        &auctions::id(),
+        &dealer,
+        &auction,
+        &dealer_nft,
+        &nft_mint,
+        0,
+    );
+    send(context, &[deposit], &[]).await.unwrap();
+    nft_mint
+}
+
+#[tokio::test]
+async fn test_accept_collection_bid() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let dealer = context.payer.pubkey();
+    let collection = Pubkey::new_unique();
+
+    // The bidder escrows 2 NFTs' worth at 5,000 each
+    let bidder = create_wallet(&mut context, 1_000_000_000).await;
+    let bidder_token =
+        create_token_account(&mut context, &payment_mint, &bidder.pubkey(), 10_000).await;
+    let place = instruction::place_collection_bid(
+        &auctions::id(),
+        &bidder.pubkey(),
+        &bidder_token,
+        &payment_mint,
+        &collection,
+        5_000,
+        2,
+        0,
+    );
+    send(&mut context, &[place], &[&bidder]).await.unwrap();
+
+    let (bid_address, _) =
+        find_collection_bid_address(&auctions::id(), &collection, &bidder.pubkey());
+    let bid_token =
+        spl_associated_token_account::get_associated_token_address(&bid_address, &payment_mint);
+    let dealer_token = create_token_account(&mut context, &payment_mint, &dealer, 0).await;
+    let fee_vault_token = find_fee_vault_token_address(&auctions::id(), &payment_mint);
+    assert_eq!(token_balance(&mut context, &bid_token).await, 10_000);
+
+    // An NFT of another collection cannot fill the bid
+    let stray_id = [2; 32];
+    let stray_mint = list_nft(&mut context, &payment_mint, stray_id, &Pubkey::new_unique()).await;
+    let stray = load_auction(&mut context, &stray_id).await;
+    let accept = instruction::accept_collection_bid(
+        &auctions::id(),
+        &stray,
+        &dealer,
+        &stray_mint,
+        &collection,
+        &bidder.pubkey(),
+        &dealer_token,
+        5_000,
+    );
+    let err = send(&mut context, &[accept], &[]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::NotInBidCollection));
+
+    let bid: CollectionBid = load_account(&mut context, &bid_address).await.unwrap();
+    assert_eq!(bid.quantity, 2);
+    assert_eq!(token_balance(&mut context, &bid_token).await, 10_000);
+    assert_eq!(
+        load_auction(&mut context, &stray_id).await.status,
+        AuctionStatus::Active
+    );
+
+    // A member sells to it for the bid price, less the 0.5% fee
+    let auction_id = [1; 32];
+    let nft_mint = list_nft(&mut context, &payment_mint, auction_id, &collection).await;
+    let auction = load_auction(&mut context, &auction_id).await;
+    let accept = instruction::accept_collection_bid(
+        &auctions::id(),
+        &auction,
+        &dealer,
+        &nft_mint,
+        &collection,
+        &bidder.pubkey(),
+        &dealer_token,
+        5_000,
+    );
+    send(&mut context, &[accept], &[]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &bid_token).await, 5_000);
+    assert_eq!(token_balance(&mut context, &dealer_token).await, 4_975);
+    assert_eq!(token_balance(&mut context, &fee_vault_token).await, 25);
+    let bid: CollectionBid = load_account(&mut context, &bid_address).await.unwrap();
+    assert_eq!(bid.quantity, 1);
+    let auction = load_auction(&mut context, &auction_id).await;
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_bidder, bidder.pubkey());
+    assert_eq!(auction.current_bid, 5_000);
+}
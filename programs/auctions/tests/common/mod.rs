@@ -0,0 +1,257 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test binary uses a different subset, so unused ones are allowed.
+#![allow(dead_code)]
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, CreateIndexes},
+    pda::find_auction_address,
+    state::{Auction, PodAccount, ProgramAccount},
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{
+    processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+/// Failure of a single-instruction transaction with `err`
+pub fn auction_error(err: AuctionError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(err as u32))
+}
+
+/// Start the program with its state initialized by the context payer
+pub async fn setup() -> ProgramTestContext {
+    let program_test = ProgramTest::new(
+        "auctions",
+        auctions::id(),
+        processor!(auctions::process_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let init = instruction::initialize(&auctions::id(), &payer);
+    send(&mut context, &[init], &[]).await.unwrap();
+    context
+}
+
+/// Send `instructions` paid for by the context payer and signed by `signers`
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    // Identical transactions in one block would be deduplicated
+    let blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = blockhash;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err: BanksClientError| err.unwrap())
+}
+
+/// Create a mint with no decimals whose authority is the context payer
+pub async fn create_mint(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(&spl_token::id(), &mint.pubkey(), &payer, None, 0)
+            .unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Create `owner`'s associated token account for `mint` holding `amount`
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let account = spl_associated_token_account::get_associated_token_address(owner, mint);
+    let mut instructions = vec![
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &payer,
+            owner,
+            mint,
+            &spl_token::id(),
+        ),
+    ];
+    if amount > 0 {
+        instructions.push(
+            spl_token::instruction::mint_to(&spl_token::id(), mint, &account, &payer, &[], amount)
+                .unwrap(),
+        );
+    }
+    send(context, &instructions, &[]).await.unwrap();
+    account
+}
+
+/// Create a wallet funded with `lamports` by the context payer
+pub async fn create_wallet(context: &mut ProgramTestContext, lamports: u64) -> Keypair {
+    let wallet = Keypair::new();
+    let payer = context.payer.pubkey();
+    let transfer = system_instruction::transfer(&payer, &wallet.pubkey(), lamports);
+    send(context, &[transfer], &[]).await.unwrap();
+    wallet
+}
+
+/// Token balance of `account`, 0 if it does not exist
+pub async fn token_balance(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    match context.banks_client.get_account(*account).await.unwrap() {
+        Some(account) => {
+            spl_token::state::Account::unpack(&account.data)
+                .unwrap()
+                .amount
+        }
+        None => 0,
+    }
+}
+
+/// Move the clock to `unix_timestamp`
+pub async fn set_time(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.set_sysvar(&Clock {
+        unix_timestamp,
+        ..clock
+    });
+}
+
+/// Current clock timestamp
+pub async fn now(context: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
+
+pub async fn load_auction(context: &mut ProgramTestContext, auction_id: &[u8; 32]) -> Auction {
+    let (address, _) = find_auction_address(&auctions::id(), auction_id);
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    Auction::load(&account.data).unwrap()
+}
+
+/// Load a program account, `None` if it does not exist
+pub async fn load_account<T: ProgramAccount>(
+    context: &mut ProgramTestContext,
+    address: &Pubkey,
+) -> Option<T> {
+    let account = context.banks_client.get_account(*address).await.unwrap()?;
+    Some(T::load(&account.data).unwrap())
+}
+
+/// Create a traditional auction by the context payer, open for an hour,
+/// starting at `start_amount` with no reserve
+pub async fn create_traditional_auction(
+    context: &mut ProgramTestContext,
+    payment_mint: &Pubkey,
+    auction_id: [u8; 32],
+    start_amount: u64,
+) -> Auction {
+    let dealer = context.payer.pubkey();
+    let deadline = now(context).await + 3_600;
+    let create = instruction::create_traditional_auction(
+        &auctions::id(),
+        &dealer,
+        payment_mint,
+        &CreateIndexes::default(),
+        auction_id,
+        start_amount,
+        100,
+        0,
+        deadline,
+        0,
+        "",
+        &[],
+        [0; 32],
+    );
+    send(context, &[create], &[]).await.unwrap();
+    load_auction(context, &auction_id).await
+}
+
+/// Create a Dutch auction by the context payer, open for an hour, and
+/// deposit `amount` of a fresh item mint into it
+///
+/// Returns the auction and the item mint.
+pub async fn create_dutch_auction(
+    context: &mut ProgramTestContext,
+    payment_mint: &Pubkey,
+    auction_id: [u8; 32],
+    start_price: u64,
+    decrease_amount: u64,
+    minimum_price: u64,
+    amount: u64,
+) -> (Auction, Pubkey) {
+    let dealer = context.payer.pubkey();
+    let deadline = now(context).await + 3_600;
+    let create = instruction::create_dutch_auction(
+        &auctions::id(),
+        &dealer,
+        payment_mint,
+        &CreateIndexes::default(),
+        auction_id,
+        start_price,
+        decrease_amount,
+        60,
+        minimum_price,
+        deadline,
+        0,
+        "",
+        &[],
+    );
+    send(context, &[create], &[]).await.unwrap();
+
+    let item_mint = create_mint(context).await;
+    let dealer_token = create_token_account(context, &item_mint, &dealer, amount).await;
+    let auction = load_auction(context, &auction_id).await;
+    let deposit = instruction::deposit_tokens(
+        &auctions::id(),
+        &dealer,
+        &auction,
+        &dealer_token,
+        &item_mint,
+        amount,
+        0,
+    );
+    send(context, &[deposit], &[]).await.unwrap();
+    (load_auction(context, &auction_id).await, item_mint)
+}
+
+/// Initialize the fee vault of `payment_mint`
+pub async fn init_fee_vault(context: &mut ProgramTestContext, payment_mint: &Pubkey) {
+    let payer = context.payer.pubkey();
+    let init = instruction::init_fee_vault(&auctions::id(), &payer, payment_mint);
+    send(context, &[init], &[]).await.unwrap();
+}
@@ -0,0 +1,323 @@
+//! Dealer as a PDA signer
+//!
+//! A mock governance program holds a system-owned treasury PDA and forwards
+//! dealer instructions to the auctions program with `invoke_signed`, the way
+//! a DAO runs treasury auctions through a passed proposal.
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, CreateIndexes},
+    pda::find_auction_address,
+    state::{Auction, PodAccount},
+};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::InstructionError,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const TREASURY_SEED: &[u8] = b"treasury";
+
+fn governance_id() -> Pubkey {
+    Pubkey::new_from_array([42; 32])
+}
+
+fn find_treasury_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &governance_id())
+}
+
+/// Mock governance: executes the instruction whose data it was given against
+/// `accounts[1]`, signing for the treasury PDA in `accounts[0]`
+///
+/// Accounts:
+/// 0. `[writable]` Treasury PDA
+/// 1. `[]` Target program
+/// 2. `..` Accounts of the forwarded instruction
+fn process_governance(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let treasury = &accounts[0];
+    let target = &accounts[1];
+    let (expected, bump) = Pubkey::find_program_address(&[TREASURY_SEED], program_id);
+    assert_eq!(*treasury.key, expected);
+
+    let inner = &accounts[2..];
+    let ix = Instruction {
+        program_id: *target.key,
+        accounts: inner
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer || info.key == treasury.key,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    let mut infos = inner.to_vec();
+    infos.push(target.clone());
+    invoke_signed(&ix, &infos, &[&[TREASURY_SEED, &[bump]]])
+}
+
+/// Route `ix` through the mock governance program; the treasury's signature
+/// is supplied by the CPI instead of the transaction
+fn governed(ix: Instruction) -> Instruction {
+    let (treasury, _) = find_treasury_address();
+    let mut accounts = vec![
+        AccountMeta::new(treasury, false),
+        AccountMeta::new_readonly(ix.program_id, false),
+    ];
+    accounts.extend(ix.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != treasury,
+        ..meta
+    }));
+    Instruction {
+        program_id: governance_id(),
+        accounts,
+        data: ix.data,
+    }
+}
+
+async fn setup() -> (ProgramTestContext, Pubkey) {
+    let mut program_test = ProgramTest::new(
+        "auctions",
+        auctions::id(),
+        processor!(auctions::process_instruction),
+    );
+    program_test.add_program(
+        "mock_governance",
+        governance_id(),
+        processor!(process_governance),
+    );
+    let (treasury, _) = find_treasury_address();
+    program_test.add_account(
+        treasury,
+        Account {
+            lamports: 10_000_000_000,
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let init = instruction::initialize(&auctions::id(), &payer);
+    send(&mut context, &[init], &[]).await.unwrap();
+    (context, treasury)
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+async fn create_mint(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(&spl_token::id(), &mint.pubkey(), &payer, None, 0)
+            .unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            &account.pubkey(),
+            &payer,
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&account]).await.unwrap();
+    account.pubkey()
+}
+
+async fn load_auction(context: &mut ProgramTestContext, auction_id: &[u8; 32]) -> Auction {
+    let (address, _) = find_auction_address(&auctions::id(), auction_id);
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    Auction::load(&account.data).unwrap()
+}
+
+async fn create_auction(
+    context: &mut ProgramTestContext,
+    treasury: &Pubkey,
+    mint: &Pubkey,
+    auction_id: [u8; 32],
+) -> Result<(), TransactionError> {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let ix = instruction::create_traditional_auction(
+        &auctions::id(),
+        treasury,
+        mint,
+        &CreateIndexes::default(),
+        auction_id,
+        1_000,
+        100,
+        0,
+        clock.unix_timestamp + 3_600,
+        0,
+        "",
+        &[],
+        [0; 32],
+    );
+    send(context, &[governed(ix)], &[]).await
+}
+
+#[tokio::test]
+async fn test_governance_creates_and_stocks_auction() {
+    let (mut context, treasury) = setup().await;
+    let mint = create_mint(&mut context).await;
+    let treasury_token = create_token_account(&mut context, &mint, &treasury, 10).await;
+    let auction_id = [1; 32];
+
+    create_auction(&mut context, &treasury, &mint, auction_id)
+        .await
+        .unwrap();
+    let auction = load_auction(&mut context, &auction_id).await;
+    assert_eq!(auction.dealer, treasury);
+
+    let deposit = instruction::deposit_tokens(
+        &auctions::id(),
+        &treasury,
+        &auction,
+        &treasury_token,
+        &mint,
+        10,
+        0,
+    );
+    send(&mut context, &[governed(deposit)], &[]).await.unwrap();
+    assert_eq!(load_auction(&mut context, &auction_id).await.item_count, 1);
+
+    let delegate = Pubkey::new_unique();
+    let set_delegate =
+        instruction::set_auction_delegate(&auctions::id(), &treasury, &auction_id, &delegate);
+    send(&mut context, &[governed(set_delegate)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        load_auction(&mut context, &auction_id).await.delegate,
+        delegate
+    );
+}
+
+#[tokio::test]
+async fn test_treasury_requires_governance_signature() {
+    let (mut context, treasury) = setup().await;
+    let mint = create_mint(&mut context).await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let mut ix = instruction::create_traditional_auction(
+        &auctions::id(),
+        &treasury,
+        &mint,
+        &CreateIndexes::default(),
+        [2; 32],
+        1_000,
+        100,
+        0,
+        clock.unix_timestamp + 3_600,
+        0,
+        "",
+        &[],
+        [0; 32],
+    );
+    // Nobody holds a key for the treasury, so it can only be passed unsigned
+    ix.accounts[0].is_signer = false;
+
+    let err = send(&mut context, &[ix], &[]).await.unwrap_err();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+}
+
+#[tokio::test]
+async fn test_governance_cannot_lend_other_signers_dealer_rights() {
+    let (mut context, treasury) = setup().await;
+    let mint = create_mint(&mut context).await;
+    let auction_id = [3; 32];
+    create_auction(&mut context, &treasury, &mint, auction_id)
+        .await
+        .unwrap();
+
+    // Signing through governance only lends the treasury's own authority
+    let rival = Pubkey::new_unique();
+    let other = Keypair::new();
+    let hijack =
+        instruction::set_auction_delegate(&auctions::id(), &other.pubkey(), &auction_id, &rival);
+    let err = send(&mut context, &[governed(hijack)], &[&other])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AuctionError::OnlyDealer as u32)
+        )
+    );
+}
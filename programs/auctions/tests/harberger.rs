@@ -0,0 +1,130 @@
+//! Harberger listing: anyone can force-buy the item at its assessed price,
+//! the holder paying tax on that price out of a prepaid deposit
+
+mod common;
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, GateAccounts},
+    pda::{find_escrow_address, find_fee_vault_token_address, find_harberger_listing_address},
+    state::HarbergerListing,
+};
+use common::*;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_force_buy_pays_price_and_tax() {
+    let mut context = setup().await;
+    let payment_mint = create_mint(&mut context).await;
+    init_fee_vault(&mut context, &payment_mint).await;
+    let auction_id = [1; 32];
+    let (auction, _) = create_dutch_auction(
+        &mut context,
+        &payment_mint,
+        auction_id,
+        10_000,
+        1_000,
+        5_000,
+        1,
+    )
+    .await;
+    let dealer = context.payer.pubkey();
+    // Listed at 10,000, taxed 10% of the assessed price an hour
+    let set = instruction::set_harberger_listing(
+        &auctions::id(),
+        &dealer,
+        &auction_id,
+        10_000,
+        1_000,
+        3_600,
+    );
+    send(&mut context, &[set], &[]).await.unwrap();
+
+    let (listing_address, _) = find_harberger_listing_address(&auctions::id(), &auction_id);
+    let (escrow, _) = find_escrow_address(&auctions::id(), &auction_id);
+    let fee_vault_token = find_fee_vault_token_address(&auctions::id(), &payment_mint);
+    let dealer_token =
+        spl_associated_token_account::get_associated_token_address(&dealer, &payment_mint);
+
+    // Alice buys from the dealer at the list price, which pays the fee like
+    // a Dutch buy, and prepays 5,000 of tax on her 20,000 assessment
+    let alice = create_wallet(&mut context, 1_000_000_000).await;
+    let alice_token =
+        create_token_account(&mut context, &payment_mint, &alice.pubkey(), 40_000).await;
+    let buy = instruction::force_buy(
+        &auctions::id(),
+        &auction,
+        &alice.pubkey(),
+        &alice_token,
+        &dealer,
+        10_000,
+        20_000,
+        5_000,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[buy], &[&alice]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &alice_token).await, 25_000);
+    assert_eq!(token_balance(&mut context, &dealer_token).await, 9_950);
+    assert_eq!(token_balance(&mut context, &fee_vault_token).await, 50);
+    assert_eq!(token_balance(&mut context, &escrow).await, 5_000);
+    let listing: HarbergerListing = load_account(&mut context, &listing_address).await.unwrap();
+    assert_eq!(listing.holder, alice.pubkey());
+    assert_eq!(
+        (listing.assessed_price, listing.last_price, listing.deposit),
+        (20_000, 10_000, 5_000)
+    );
+    assert_eq!(listing.tax_collected, 0);
+
+    // Half an hour later Alice owes 1,000 of tax
+    let start = now(&mut context).await;
+    set_time(&mut context, start + 1_800).await;
+    let bob = create_wallet(&mut context, 1_000_000_000).await;
+    let bob_token = create_token_account(&mut context, &payment_mint, &bob.pubkey(), 30_000).await;
+    let auction = load_auction(&mut context, &auction_id).await;
+
+    // The assessed price, not the list price, is what Bob pays
+    let buy = instruction::force_buy(
+        &auctions::id(),
+        &auction,
+        &bob.pubkey(),
+        &bob_token,
+        &alice.pubkey(),
+        19_999,
+        30_000,
+        3_000,
+        &GateAccounts::default(),
+    );
+    let err = send(&mut context, &[buy], &[&bob]).await.unwrap_err();
+    assert_eq!(err, auction_error(AuctionError::SlippageExceeded));
+
+    let buy = instruction::force_buy(
+        &auctions::id(),
+        &auction,
+        &bob.pubkey(),
+        &bob_token,
+        &alice.pubkey(),
+        20_000,
+        30_000,
+        3_000,
+        &GateAccounts::default(),
+    );
+    send(&mut context, &[buy], &[&bob]).await.unwrap();
+
+    // Alice gets the 20,000 and the 4,000 of her deposit left after tax; the
+    // tax stays escrowed for the dealer next to Bob's deposit, and a sale
+    // between holders pays no fee
+    assert_eq!(token_balance(&mut context, &alice_token).await, 49_000);
+    assert_eq!(token_balance(&mut context, &bob_token).await, 7_000);
+    assert_eq!(token_balance(&mut context, &escrow).await, 4_000);
+    assert_eq!(token_balance(&mut context, &dealer_token).await, 9_950);
+    assert_eq!(token_balance(&mut context, &fee_vault_token).await, 50);
+    let listing: HarbergerListing = load_account(&mut context, &listing_address).await.unwrap();
+    assert_eq!(listing.holder, bob.pubkey());
+    assert_eq!(
+        (listing.assessed_price, listing.last_price, listing.deposit),
+        (30_000, 20_000, 3_000)
+    );
+    assert_eq!(listing.tax_collected, 1_000);
+    assert_eq!(listing.last_accrued, start + 1_800);
+}
@@ -0,0 +1,245 @@
+//! Signed bids: a matcher submits a bid the bidder signed off-chain, verified
+//! by the Ed25519 program and paid through the bidder's own delegate
+
+mod common;
+
+use auctions::{
+    error::AuctionError,
+    instruction::{self, GateAccounts},
+    pda::{find_bid_delegate_address, find_escrow_address},
+    signed_bid::SignedBidOrder,
+    state::{Auction, BidDelegate},
+};
+use common::*;
+use solana_program::{ed25519_program, instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Ed25519 program instruction verifying `signer`'s signature over
+/// `message`, with every part referenced by `index`, laid out as
+/// `new_ed25519_instruction` does
+fn ed25519_instruction(signer: &Keypair, message: &[u8], index: u16) -> Instruction {
+    let (key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        index,
+        key_offset,
+        index,
+        message_offset,
+        message.len() as u16,
+        index,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Failure of the `SettleSignedBid` following the Ed25519 instruction
+fn settle_error(err: AuctionError) -> TransactionError {
+    TransactionError::InstructionError(1, InstructionError::Custom(err as u32))
+}
+
+/// Traditional auction of 10 tokens opening at 1,000, and a bidder holding
+/// 10,000 who approved their bid delegate for all of it
+async fn setup_auction(context: &mut ProgramTestContext) -> (Auction, Keypair, Pubkey) {
+    let payment_mint = create_mint(context).await;
+    let auction_id = [1; 32];
+    let auction = create_traditional_auction(context, &payment_mint, auction_id, 1_000).await;
+    let dealer = context.payer.pubkey();
+    let item_mint = create_mint(context).await;
+    let dealer_item = create_token_account(context, &item_mint, &dealer, 10).await;
+    let deposit = instruction::deposit_tokens(
+        &auctions::id(),
+        &dealer,
+        &auction,
+        &dealer_item,
+        &item_mint,
+        10,
+        0,
+    );
+    send(context, &[deposit], &[]).await.unwrap();
+
+    let bidder = Keypair::new();
+    let bidder_token = create_token_account(context, &payment_mint, &bidder.pubkey(), 10_000).await;
+    let (delegate, _) = find_bid_delegate_address(&auctions::id(), &bidder.pubkey());
+    let approve = spl_token::instruction::approve(
+        &spl_token::id(),
+        &bidder_token,
+        &delegate,
+        &bidder.pubkey(),
+        &[],
+        10_000,
+    )
+    .unwrap();
+    send(context, &[approve], &[&bidder]).await.unwrap();
+
+    (
+        load_auction(context, &auction_id).await,
+        bidder,
+        bidder_token,
+    )
+}
+
+/// `SettleSignedBid` for `order`, submitted by the context payer
+fn settle(
+    context: &ProgramTestContext,
+    auction: &Auction,
+    bidder: &Pubkey,
+    bidder_token: &Pubkey,
+    order: &SignedBidOrder,
+) -> Instruction {
+    instruction::settle_signed_bid(
+        &auctions::id(),
+        auction,
+        &context.payer.pubkey(),
+        bidder,
+        bidder_token,
+        &Pubkey::new_unique(),
+        order.amount,
+        order.expires_at,
+        order.nonce,
+        &GateAccounts::default(),
+    )
+}
+
+#[tokio::test]
+async fn test_settle_signed_bid_once() {
+    let mut context = setup().await;
+    let (auction, bidder, bidder_token) = setup_auction(&mut context).await;
+    let (escrow, _) = find_escrow_address(&auctions::id(), &auction.auction_id);
+    let (delegate, _) = find_bid_delegate_address(&auctions::id(), &bidder.pubkey());
+
+    let order = SignedBidOrder {
+        auction_id: auction.auction_id,
+        amount: 1_000,
+        expires_at: now(&mut context).await + 600,
+        nonce: 0,
+    };
+    let verify = ed25519_instruction(&bidder, &order.message(&auctions::id()), u16::MAX);
+    let submit = settle(&context, &auction, &bidder.pubkey(), &bidder_token, &order);
+    send(&mut context, &[verify.clone(), submit.clone()], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(token_balance(&mut context, &bidder_token).await, 9_000);
+    assert_eq!(token_balance(&mut context, &escrow).await, 1_000);
+    let auction = load_auction(&mut context, &auction.auction_id).await;
+    assert_eq!(auction.current_bidder, bidder.pubkey());
+    assert_eq!(auction.current_bid, 1_000);
+    let state: BidDelegate = load_account(&mut context, &delegate).await.unwrap();
+    assert_eq!(state.bidder, bidder.pubkey());
+    assert_eq!(state.nonce, 1);
+
+    // The same signature cannot settle again, even for an amount the
+    // auction would take
+    let err = send(&mut context, &[verify, submit], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::InvalidSignedBidNonce));
+    assert_eq!(token_balance(&mut context, &bidder_token).await, 9_000);
+
+    // The bidder's next order carries the next nonce
+    let raised = SignedBidOrder {
+        amount: 1_100,
+        nonce: 1,
+        ..order
+    };
+    let verify = ed25519_instruction(&bidder, &raised.message(&auctions::id()), u16::MAX);
+    let submit = settle(&context, &auction, &bidder.pubkey(), &bidder_token, &raised);
+    send(&mut context, &[verify, submit], &[]).await.unwrap();
+
+    let auction = load_auction(&mut context, &auction.auction_id).await;
+    assert_eq!(auction.current_bid, 1_100);
+    let state: BidDelegate = load_account(&mut context, &delegate).await.unwrap();
+    assert_eq!(state.nonce, 2);
+}
+
+#[tokio::test]
+async fn test_settle_signed_bid_rejects_bad_orders() {
+    let mut context = setup().await;
+    let (auction, bidder, bidder_token) = setup_auction(&mut context).await;
+    let order = SignedBidOrder {
+        auction_id: auction.auction_id,
+        amount: 1_000,
+        expires_at: now(&mut context).await + 600,
+        nonce: 0,
+    };
+    let submit = settle(&context, &auction, &bidder.pubkey(), &bidder_token, &order);
+
+    // A signature over other terms does not cover the order
+    let other = SignedBidOrder {
+        amount: 2_000,
+        ..order
+    };
+    let verify = ed25519_instruction(&bidder, &other.message(&auctions::id()), u16::MAX);
+    let err = send(&mut context, &[verify, submit.clone()], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::InvalidSignedBid));
+
+    // Offsets into another instruction are not trusted, though the Ed25519
+    // program accepts them
+    let verify = ed25519_instruction(&bidder, &order.message(&auctions::id()), 0);
+    let err = send(&mut context, &[verify, submit.clone()], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::InvalidSignedBid));
+
+    // A valid signature for the wrong signer
+    let impostor = Keypair::new();
+    let verify = ed25519_instruction(&impostor, &order.message(&auctions::id()), u16::MAX);
+    let err = send(&mut context, &[verify, submit], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::InvalidSignedBid));
+
+    // An order past its expiry is void even with a valid signature
+    let expired = SignedBidOrder {
+        expires_at: now(&mut context).await - 1,
+        ..order
+    };
+    let verify = ed25519_instruction(&bidder, &expired.message(&auctions::id()), u16::MAX);
+    let submit = settle(
+        &context,
+        &auction,
+        &bidder.pubkey(),
+        &bidder_token,
+        &expired,
+    );
+    let err = send(&mut context, &[verify, submit], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::SignedBidExpired));
+
+    // A nonce ahead of the delegate's is rejected too
+    let skipped = SignedBidOrder { nonce: 1, ..order };
+    let verify = ed25519_instruction(&bidder, &skipped.message(&auctions::id()), u16::MAX);
+    let submit = settle(
+        &context,
+        &auction,
+        &bidder.pubkey(),
+        &bidder_token,
+        &skipped,
+    );
+    let err = send(&mut context, &[verify, submit], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, settle_error(AuctionError::InvalidSignedBidNonce));
+
+    // Nothing was paid or bid
+    assert_eq!(token_balance(&mut context, &bidder_token).await, 10_000);
+    let auction = load_auction(&mut context, &auction.auction_id).await;
+    assert_eq!(auction.current_bidder, Pubkey::default());
+}